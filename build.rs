@@ -0,0 +1,108 @@
+//! Validates the shaders with naga and checks their interface against what the pipelines in the rust code expect,
+//! so mistakes show up as build errors instead of panics when the pipelines are created
+
+use naga::{AddressSpace, ImageClass, ShaderStage, TypeInner};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindingKind {
+    Uniform,
+    Storage,
+    Texture,
+    StorageTexture,
+}
+
+struct Expected {
+    path: &'static str,
+    /// Must match the workgroup sizes used when dispatching
+    workgroup_size: [u32; 3],
+    /// `(group, binding, kind)`, must match the bind group layouts
+    bindings: &'static [(u32, u32, BindingKind)],
+}
+
+const SHADERS: &[Expected] = &[
+    Expected {
+        path: "src/shader.wgsl",
+        workgroup_size: [16, 16, 1],
+        bindings: &[
+            (0, 0, BindingKind::StorageTexture),
+            (1, 0, BindingKind::Uniform),
+            (2, 0, BindingKind::Uniform),
+        ],
+    },
+    Expected {
+        path: "src/scopes.wgsl",
+        workgroup_size: [16, 16, 1],
+        bindings: &[(0, 0, BindingKind::Texture), (0, 1, BindingKind::Storage)],
+    },
+];
+
+fn main() {
+    let mut errors = vec![];
+    for expected in SHADERS {
+        println!("cargo:rerun-if-changed={}", expected.path);
+        if let Err(error) = check(expected) {
+            errors.push(error);
+        }
+    }
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("{error}");
+        }
+        panic!("{} shader(s) failed to validate", errors.len());
+    }
+}
+
+fn check(expected: &Expected) -> Result<(), String> {
+    let path = expected.path;
+    let source = std::fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let module = naga::front::wgsl::parse_str(&source)
+        .map_err(|error| error.emit_to_string_with_path(&source, path))?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .map_err(|error| error.emit_to_string_with_path(&source, path))?;
+
+    let entry_point = module
+        .entry_points
+        .iter()
+        .find(|entry_point| entry_point.name == "main" && entry_point.stage == ShaderStage::Compute)
+        .ok_or_else(|| format!("{path}: missing compute entry point `main`"))?;
+    if entry_point.workgroup_size != expected.workgroup_size {
+        return Err(format!(
+            "{path}: workgroup size is {:?} but the rust code dispatches with {:?}",
+            entry_point.workgroup_size, expected.workgroup_size
+        ));
+    }
+
+    let mut bindings = module
+        .global_variables
+        .iter()
+        .filter_map(|(_, variable)| {
+            let binding = variable.binding.as_ref()?;
+            let kind = match (variable.space, &module.types[variable.ty].inner) {
+                (AddressSpace::Uniform, _) => BindingKind::Uniform,
+                (AddressSpace::Storage { .. }, _) => BindingKind::Storage,
+                (
+                    AddressSpace::Handle,
+                    TypeInner::Image {
+                        class: ImageClass::Storage { .. },
+                        ..
+                    },
+                ) => BindingKind::StorageTexture,
+                (AddressSpace::Handle, TypeInner::Image { .. }) => BindingKind::Texture,
+                _ => return None,
+            };
+            Some((binding.group, binding.binding, kind))
+        })
+        .collect::<Vec<_>>();
+    bindings.sort_by_key(|&(group, binding, _)| (group, binding));
+    if bindings != expected.bindings {
+        return Err(format!(
+            "{path}: bindings are {bindings:?} but the bind group layouts expect {:?}",
+            expected.bindings
+        ));
+    }
+    Ok(())
+}
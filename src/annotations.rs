@@ -0,0 +1,112 @@
+use crate::localization::tr;
+use cgmath::Vector2;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// A text label pinned to the world, with an arrow to the point it's about when that differs from the label
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub text: String,
+    pub label: Vector2<f32>,
+    pub target: Vector2<f32>,
+}
+
+/// Labels and arrows drawn over the viewport, for explaining what's on screen in screenshots
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Annotations {
+    annotations: Vec<Annotation>,
+    /// The next click in the viewport adds an annotation there
+    #[serde(skip)]
+    placing: bool,
+}
+
+impl Annotations {
+    const KEY: &'static str = "annotations";
+
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|storage| eframe::get_value(storage, Self::KEY))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::KEY, self);
+    }
+
+    /// Adds an annotation where the viewport was clicked, if the user is placing one
+    pub fn handle_click(&mut self, world_position: Vector2<f32>) {
+        if std::mem::take(&mut self.placing) {
+            self.annotations.push(Annotation {
+                text: tr!("annotations-default-text"),
+                label: world_position,
+                target: world_position,
+            });
+        }
+    }
+
+    /// Draws every annotation, `to_screen` maps world positions into the viewport
+    pub fn paint(&self, painter: &egui::Painter, to_screen: impl Fn(Vector2<f32>) -> egui::Pos2) {
+        let style = painter.ctx().style();
+        let visuals = &style.visuals;
+        let stroke = egui::Stroke::new(2.0, visuals.strong_text_color());
+        for annotation in &self.annotations {
+            let label = to_screen(annotation.label);
+            let target = to_screen(annotation.target);
+            if label.distance(target) > 1.0 {
+                painter.arrow(label, target - label, stroke);
+            }
+            let galley = painter.layout_no_wrap(
+                annotation.text.clone(),
+                egui::FontId::proportional(16.0),
+                visuals.strong_text_color(),
+            );
+            let rect = egui::Align2::CENTER_BOTTOM
+                .anchor_rect(egui::Rect::from_min_size(label, galley.size()))
+                .translate(egui::vec2(0.0, -4.0));
+            painter.rect_filled(
+                rect.expand(4.0),
+                4.0,
+                visuals.extreme_bg_color.gamma_multiply(0.8),
+            );
+            painter.galley(rect.min, galley);
+        }
+    }
+
+    /// Lists the annotations for editing, `center` is where new ones are added
+    pub fn ui(&mut self, ui: &mut egui::Ui, center: Vector2<f32>) {
+        ui.horizontal(|ui| {
+            if ui.button(tr!("annotations-add")).clicked() {
+                self.annotations.push(Annotation {
+                    text: tr!("annotations-default-text"),
+                    label: center,
+                    target: center,
+                });
+            }
+            ui.toggle_value(&mut self.placing, tr!("annotations-place"))
+                .on_hover_text(tr!("annotations-place-hint"));
+        });
+
+        let mut removed = None;
+        egui::Grid::new("Annotations").show(ui, |ui| {
+            for (i, annotation) in self.annotations.iter_mut().enumerate() {
+                ui.text_edit_singleline(&mut annotation.text);
+                ui.label(tr!("annotations-label"));
+                ui.add(egui::DragValue::new(&mut annotation.label.x).speed(0.01));
+                ui.add(egui::DragValue::new(&mut annotation.label.y).speed(0.01));
+                ui.label(tr!("annotations-target"));
+                ui.add(egui::DragValue::new(&mut annotation.target.x).speed(0.01));
+                ui.add(egui::DragValue::new(&mut annotation.target.y).speed(0.01));
+                if ui.button(tr!("annotations-remove")).clicked() {
+                    removed = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed {
+            self.annotations.remove(i);
+        }
+        if self.annotations.is_empty() {
+            ui.weak(tr!("annotations-empty"));
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{diagnostics::Diagnostics, localization::tr};
+use anyhow::Context as _;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Periodically writes snapshots of the editor state to a temp file on a background thread
+///
+/// Each instance writes its own file next to a lock file it holds until it exits, the files are removed when
+/// the app exits normally, so finding one whose lock is free on startup means that session crashed
+pub struct Autosave {
+    path: PathBuf,
+    /// Released by the os when the process exits, even if it crashed
+    lock: Option<File>,
+    /// The newest snapshot that hasn't been written yet, shared with the writer thread and the panic hook
+    pending: Arc<Mutex<Option<String>>>,
+    wake_writer: Sender<()>,
+    last_snapshot: Instant,
+    last_written: String,
+}
+
+impl Autosave {
+    /// Returns the autosave along with the snapshot left behind by a crashed session, if there is one
+    pub fn new(diagnostics: Diagnostics) -> (Self, Option<String>) {
+        let directory = std::env::temp_dir().join("raytracing_2d_autosave");
+        let path = directory.join(format!("{}.json", std::process::id()));
+        // taken before looking for crashed sessions, so an instance starting at the same time doesn't think this one crashed
+        let lock = lock(&path.with_extension("lock"))
+            .map_err(|error| tracing::error!("failed to lock the autosave: {error:#}"))
+            .ok();
+        let recovered = recover(&directory, &path);
+
+        let pending = Arc::new(Mutex::new(None::<String>));
+        let (wake_writer, wakes) = mpsc::channel::<()>();
+        let spawned = thread::Builder::new().name("Autosave".into()).spawn({
+            let path = path.clone();
+            let pending = pending.clone();
+            move || {
+                // the sender is dropped when the app exits, which stops the thread
+                for () in wakes {
+                    // the lock is held while writing so `remove` can't race with a write
+                    let mut pending = pending.lock().unwrap();
+                    if let Some(snapshot) = pending.take() {
+                        if let Err(error) = write_atomically(&path, &snapshot) {
+                            diagnostics.report_error(&tr!("autosave-failed"), &error);
+                        }
+                    }
+                }
+            }
+        });
+        if let Err(error) = spawned {
+            tracing::error!("failed to start the autosave thread: {error}");
+        }
+
+        // flush the newest snapshot before unwinding, the writer thread may never get to it
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new({
+            let path = path.clone();
+            let pending = pending.clone();
+            move |info| {
+                if let Ok(mut pending) = pending.try_lock() {
+                    if let Some(snapshot) = pending.take() {
+                        _ = write_atomically(&path, &snapshot);
+                    }
+                }
+                previous_hook(info);
+            }
+        }));
+
+        (
+            Self {
+                path,
+                lock,
+                pending,
+                wake_writer,
+                last_snapshot: Instant::now(),
+                last_written: String::new(),
+            },
+            recovered,
+        )
+    }
+
+    /// Takes a snapshot if `interval` has passed since the last one, `snapshot` is only called when one is due
+    pub fn update(&mut self, interval: Duration, snapshot: impl FnOnce() -> String) {
+        if self.last_snapshot.elapsed() < interval {
+            return;
+        }
+        self.last_snapshot = Instant::now();
+
+        let snapshot = snapshot();
+        if snapshot == self.last_written {
+            return;
+        }
+        self.last_written = snapshot.clone();
+        *self.pending.lock().unwrap_or_else(PoisonError::into_inner) = Some(snapshot);
+        _ = self.wake_writer.send(());
+    }
+
+    /// Deletes the autosave, called when the app exits normally
+    pub fn remove(&mut self) {
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        *pending = None;
+        _ = std::fs::remove_file(&self.path);
+        if self.lock.take().is_some() {
+            _ = std::fs::remove_file(self.path.with_extension("lock"));
+        }
+    }
+}
+
+fn lock(path: &Path) -> anyhow::Result<File> {
+    std::fs::create_dir_all(path.parent().unwrap_or(path))
+        .with_context(|| format!("creating the directory for {}", path.display()))?;
+    let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+    file.try_lock()
+        .with_context(|| format!("locking {}", path.display()))?;
+    Ok(file)
+}
+
+/// Finds the autosave of a crashed session and moves it to `path`, so it's kept until this session writes its own
+fn recover(directory: &Path, path: &Path) -> Option<String> {
+    // the process id was reused, the previous owner of this file is gone
+    if let Ok(snapshot) = std::fs::read_to_string(path) {
+        return Some(snapshot);
+    }
+    let own_lock_path = path.with_extension("lock");
+    for entry in std::fs::read_dir(directory).ok()?.flatten() {
+        let lock_path = entry.path();
+        if lock_path.extension() != Some("lock".as_ref()) || lock_path == own_lock_path {
+            continue;
+        }
+        // still locked means that instance is running
+        let Ok(lock) = File::open(&lock_path) else {
+            continue;
+        };
+        if lock.try_lock().is_err() {
+            continue;
+        }
+        let crashed_path = lock_path.with_extension("json");
+        let snapshot = std::fs::read_to_string(&crashed_path).ok();
+        if snapshot.is_some() {
+            _ = std::fs::rename(&crashed_path, path);
+        }
+        drop(lock);
+        _ = std::fs::remove_file(&lock_path);
+        if snapshot.is_some() {
+            return snapshot;
+        }
+    }
+    None
+}
+
+/// Writes to a separate file first so a crash mid-write can't leave a truncated autosave
+fn write_atomically(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, contents)
+        .with_context(|| format!("writing {}", temp_path.display()))?;
+    std::fs::rename(&temp_path, path).with_context(|| format!("replacing {}", path.display()))?;
+    Ok(())
+}
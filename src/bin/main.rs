@@ -1,7 +1,50 @@
 use eframe::{egui_wgpu::WgpuConfiguration, run_native, wgpu, NativeOptions, Renderer};
-use raytracing_2d::App;
+use raytracing_2d::{logging, App, RendererKind};
+use std::str::FromStr;
+
+/// Reads `--name <value>` or `--name=<value>` from the command line
+fn arg(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix(name) {
+            Some("") => return args.next(),
+            Some(value) => {
+                if let Some(value) = value.strip_prefix('=') {
+                    return Some(value.to_owned());
+                }
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+/// Parses an argument, falling back to `default` if it's missing or invalid
+fn parse_arg<T: FromStr>(name: &str, expected: &str, default: T) -> T {
+    match arg(name).map(|value| value.parse()) {
+        Some(Ok(value)) => value,
+        Some(Err(_)) => {
+            tracing::warn!("{name} expects {expected}");
+            default
+        }
+        None => default,
+    }
+}
 
 fn main() {
+    // logging needs the level before it can report that the level is invalid
+    let log_level = arg("--log-level").map(|level| level.parse::<tracing::Level>());
+    let logs = logging::init(match &log_level {
+        Some(Ok(level)) => *level,
+        _ => tracing::Level::INFO,
+    });
+    if let Some(Err(_)) = log_level {
+        tracing::warn!("--log-level expects one of error, warn, info, debug or trace");
+    }
+    let renderer_kind = parse_arg("--renderer", "gpu or cpu", RendererKind::Gpu);
+    #[cfg(feature = "renderdoc")]
+    raytracing_2d::renderdoc_capture::init();
+
     run_native(
         "2D Ray Tracing",
         NativeOptions {
@@ -11,11 +54,29 @@ fn main() {
                 supported_backends: wgpu::Backends::all(),
                 present_mode: wgpu::PresentMode::AutoNoVsync,
                 power_preference: wgpu::PowerPreference::HighPerformance,
+                // timestamp queries for tracy's gpu zones
+                #[cfg(feature = "tracy")]
+                device_descriptor: std::sync::Arc::new(|adapter| {
+                    let base_limits = if adapter.get_info().backend == wgpu::Backend::Gl {
+                        wgpu::Limits::downlevel_webgl2_defaults()
+                    } else {
+                        wgpu::Limits::default()
+                    };
+                    wgpu::DeviceDescriptor {
+                        label: Some("egui wgpu device"),
+                        features: adapter.features() & wgpu::Features::TIMESTAMP_QUERY,
+                        limits: wgpu::Limits {
+                            // same as egui's default
+                            max_texture_dimension_2d: 8192,
+                            ..base_limits
+                        },
+                    }
+                }),
                 ..Default::default()
             },
             ..Default::default()
         },
-        Box::new(|cc| Box::new(App::new(cc))),
+        Box::new(|cc| Box::new(App::new(cc, logs, renderer_kind))),
     )
     .unwrap()
 }
@@ -0,0 +1,79 @@
+use crate::{localization::tr, timeline::CameraPose};
+use eframe::egui;
+
+/// The number key for each slot, see [`crate::commands::Command::RecallBookmark`]
+pub const KEYS: [egui::Key; 9] = [
+    egui::Key::Num1,
+    egui::Key::Num2,
+    egui::Key::Num3,
+    egui::Key::Num4,
+    egui::Key::Num5,
+    egui::Key::Num6,
+    egui::Key::Num7,
+    egui::Key::Num8,
+    egui::Key::Num9,
+];
+
+/// Numbered camera poses, saved with Ctrl and a number key and jumped to with the number key alone
+pub struct Bookmarks {
+    slots: [Option<CameraPose>; 9],
+}
+
+impl Bookmarks {
+    const KEY: &'static str = "camera_bookmarks";
+
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        Self {
+            slots: storage
+                .and_then(|storage| eframe::get_value(storage, Self::KEY))
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::KEY, &self.slots);
+    }
+
+    pub fn store(&mut self, slot: usize, pose: CameraPose) {
+        self.slots[slot] = Some(pose);
+    }
+
+    pub fn get(&self, slot: usize) -> Option<CameraPose> {
+        self.slots[slot]
+    }
+
+    /// Shows every slot, returning the pose to jump to if one was clicked
+    pub fn ui(&mut self, ui: &mut egui::Ui, current: CameraPose) -> Option<CameraPose> {
+        let mut jump = None;
+        ui.weak(tr!("bookmarks-hint"));
+        egui::Grid::new("Bookmarks").show(ui, |ui| {
+            for (i, slot) in self.slots.iter_mut().enumerate() {
+                ui.strong((i + 1).to_string());
+                match slot {
+                    Some(pose) => ui.label(format!(
+                        "x: {:.2} y: {:.2} h: {:.2}",
+                        pose.position.x, pose.position.y, pose.height
+                    )),
+                    None => ui.weak(tr!("bookmarks-empty")),
+                };
+                if ui.button(tr!("bookmarks-save")).clicked() {
+                    *slot = Some(current);
+                }
+                if ui
+                    .add_enabled(slot.is_some(), egui::Button::new(tr!("bookmarks-go-to")))
+                    .clicked()
+                {
+                    jump = *slot;
+                }
+                if ui
+                    .add_enabled(slot.is_some(), egui::Button::new(tr!("bookmarks-clear")))
+                    .clicked()
+                {
+                    *slot = None;
+                }
+                ui.end_row();
+            }
+        });
+        jump
+    }
+}
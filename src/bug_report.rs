@@ -0,0 +1,107 @@
+use crate::{diagnostics::Diagnostics, gpu_info::GpuInfo, localization::tr, logging::Logs};
+use anyhow::Context as _;
+use eframe::wgpu;
+use std::{
+    fmt::Write as _,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::SystemTime,
+};
+
+/// How many log records are included in a bundle
+const LOG_RECORDS: usize = 500;
+
+/// Writes diagnostics bundles with everything needed to make sense of a bug report
+#[derive(Clone)]
+pub struct BugReport {
+    /// Adapter info, features and limits, which don't change after startup
+    gpu: Arc<str>,
+    logs: Logs,
+    /// Updated every frame so the panic hook has something recent to write
+    scene_stats: Arc<Mutex<String>>,
+}
+
+impl BugReport {
+    pub fn new(gpu_info: &GpuInfo, logs: Logs) -> Self {
+        Self {
+            gpu: gpu_info.report().into(),
+            logs,
+            scene_stats: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    /// Writes a bundle when the app panics or the device reports an error nothing else handled
+    ///
+    /// wgpu 0.17 has no device lost callback, a lost device shows up as an uncaptured error instead
+    pub fn install_hooks(&self, device: &wgpu::Device, diagnostics: Diagnostics) {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new({
+            let bug_report = self.clone();
+            move |info| {
+                let reason = format!(
+                    "panic: {info}\n\n{}",
+                    std::backtrace::Backtrace::force_capture()
+                );
+                // the app is going down so stderr is the only place left to show the path
+                match bug_report.write(&reason) {
+                    Ok(path) => eprintln!("Diagnostics were written to {}", path.display()),
+                    Err(error) => eprintln!("Failed to write diagnostics: {error:#}"),
+                }
+                previous_hook(info);
+            }
+        }));
+
+        device.on_uncaptured_error(Box::new({
+            let bug_report = self.clone();
+            let bundle_written = AtomicBool::new(false);
+            move |error| {
+                tracing::error!("uncaptured GPU error: {error}");
+                // errors like failed validation repeat every frame, the first bundle is enough to report them
+                if bundle_written.swap(true, Ordering::Relaxed) {
+                    return;
+                }
+                match bug_report.write(&format!("GPU error: {error}")) {
+                    Ok(path) => {
+                        diagnostics.error(tr!("gpu-error", path = path.display().to_string()))
+                    }
+                    Err(error) => {
+                        diagnostics.report_error(&tr!("diagnostics-export-failed"), &error)
+                    }
+                }
+            }
+        }));
+    }
+
+    pub fn set_scene_stats(&self, stats: String) {
+        *self.scene_stats.lock().unwrap() = stats;
+    }
+
+    /// Writes a bundle to the temp directory, returning its path
+    pub fn write(&self, reason: &str) -> anyhow::Result<PathBuf> {
+        let mut text = format!(
+            "2D Ray Tracing {} on {} {}\n\n[reason]\n{reason}\n\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            self.gpu,
+        );
+        // the lock may have been poisoned by the panic that is being reported
+        if let Ok(scene_stats) = self.scene_stats.try_lock() {
+            _ = writeln!(text, "[scene]\n{scene_stats}");
+        }
+        if let Some(logs) = self.logs.recent(LOG_RECORDS) {
+            _ = write!(text, "[log]\n{logs}");
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = std::env::temp_dir().join(format!("raytracing_2d_diagnostics_{timestamp}.txt"));
+        std::fs::write(&path, text).with_context(|| format!("writing {}", path.display()))?;
+        Ok(path)
+    }
+}
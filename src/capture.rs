@@ -0,0 +1,294 @@
+use crate::localization::tr;
+use crate::{
+    diagnostics::Diagnostics,
+    frame_sink::{self, Frame, FrameSink, VideoCodec, VideoSettings},
+    jobs::Jobs,
+};
+use eframe::egui;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CaptureFormat {
+    PngSequence,
+    Gif,
+    Video,
+}
+
+impl CaptureFormat {
+    const ALL: [Self; 3] = [Self::PngSequence, Self::Gif, Self::Video];
+
+    fn name(self) -> String {
+        match self {
+            Self::PngSequence => tr!("capture-format-png-sequence"),
+            Self::Gif => tr!("capture-format-gif"),
+            Self::Video => tr!("capture-format-video"),
+        }
+    }
+}
+
+/// Feeds rendered frames into a [`FrameSink`] while a capture is running
+pub struct Capture {
+    sink: Option<Box<dyn FrameSink>>,
+    /// The size every captured frame must be rendered at, if the sink needs a fixed size
+    resolution: Option<(u32, u32)>,
+    format: CaptureFormat,
+    path: String,
+    frames_per_second: u32,
+    video: VideoSettings,
+    frames_written: usize,
+    /// Videos can't be captured at a larger width or height than the output texture can have
+    max_texture_size: u32,
+    diagnostics: Diagnostics,
+    jobs: Jobs,
+}
+
+impl Capture {
+    pub fn new(diagnostics: Diagnostics, jobs: Jobs, max_texture_size: u32) -> Self {
+        Self {
+            sink: None,
+            resolution: None,
+            format: CaptureFormat::PngSequence,
+            path: "capture".to_owned(),
+            frames_per_second: 30,
+            video: VideoSettings {
+                width: 1920,
+                height: 1080,
+                frames_per_second: 60,
+                codec: VideoCodec::H264,
+                bitrate_kbps: 20_000,
+            },
+            frames_written: 0,
+            max_texture_size,
+            diagnostics,
+            jobs,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// The size the output texture must be while capturing, instead of following the viewport
+    pub fn resolution(&self) -> Option<(u32, u32)> {
+        self.sink.as_ref().and(self.resolution)
+    }
+
+    /// The simulated time between captured frames, so animations are captured at a fixed timestep instead of in real time
+    pub fn time_step(&self) -> Option<f32> {
+        self.is_active().then(|| {
+            1.0 / match self.format {
+                CaptureFormat::PngSequence | CaptureFormat::Gif => self.frames_per_second,
+                CaptureFormat::Video => self.video.frames_per_second,
+            } as f32
+        })
+    }
+
+    pub fn start(&mut self, sink: Box<dyn FrameSink>, resolution: Option<(u32, u32)>) {
+        self.stop();
+        tracing::info!(?resolution, "capture started");
+        self.sink = Some(sink);
+        self.resolution = resolution;
+        self.frames_written = 0;
+    }
+
+    /// Starts a capture using the settings chosen in the capture window
+    pub fn start_with_settings(&mut self) {
+        // the default resolution can be larger than what small gpus support
+        self.video.width = self.clamp_size(self.video.width);
+        self.video.height = self.clamp_size(self.video.height);
+        match self.create_sink() {
+            Ok(sink) => {
+                let resolution = (self.format == CaptureFormat::Video)
+                    .then_some((self.video.width, self.video.height));
+                self.start(sink, resolution);
+            }
+            Err(error) => self
+                .diagnostics
+                .report_error(&tr!("capture-failed-to-start"), &error),
+        }
+    }
+
+    /// Stops the capture, flushing the sink in the background since encoders can take a while to finish
+    pub fn stop(&mut self) {
+        if let Some(sink) = self.sink.take() {
+            let finished = tr!(
+                "capture-finished",
+                count = self.frames_written,
+                path = self.path.as_str()
+            );
+            let diagnostics = self.diagnostics.clone();
+            self.jobs
+                .spawn(tr!("capture-finishing"), false, move |_progress| {
+                    sink.finish()?;
+                    diagnostics.info(finished);
+                    Ok(())
+                });
+        }
+    }
+
+    /// Writes the frame to the active sink, abandoning the capture if the sink fails
+    pub fn write_frame(&mut self, frame: &Frame) {
+        let Some(sink) = &mut self.sink else {
+            return;
+        };
+        match sink.write_frame(frame) {
+            Ok(()) => self.frames_written += 1,
+            Err(error) => {
+                self.diagnostics
+                    .report_error(&tr!("capture-failed"), &error);
+                // a broken sink can't be finished, trying would only report the same failure again
+                self.sink = None;
+            }
+        }
+    }
+
+    fn create_sink(&self) -> anyhow::Result<Box<dyn FrameSink>> {
+        Ok(match self.format {
+            CaptureFormat::PngSequence => Box::new(frame_sink::PngSequence::new(&self.path)?),
+            CaptureFormat::Gif => {
+                Box::new(frame_sink::Gif::new(&self.path, self.frames_per_second)?)
+            }
+            CaptureFormat::Video => Box::new(frame_sink::Ffmpeg::new(&self.path, self.video)?),
+        })
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.is_active() {
+            ui.label(tr!("capture-frames-written", count = self.frames_written));
+            if ui.button(tr!("capture-stop")).clicked() {
+                self.stop();
+            }
+        } else {
+            egui::ComboBox::from_label(tr!("capture-format"))
+                .selected_text(self.format.name())
+                .show_ui(ui, |ui| {
+                    for format in CaptureFormat::ALL {
+                        ui.selectable_value(&mut self.format, format, format.name());
+                    }
+                });
+
+            ui.horizontal(|ui| {
+                ui.label(match self.format {
+                    CaptureFormat::PngSequence => tr!("capture-directory"),
+                    CaptureFormat::Gif | CaptureFormat::Video => tr!("capture-file"),
+                });
+                ui.text_edit_singleline(&mut self.path);
+            });
+
+            match self.format {
+                CaptureFormat::PngSequence | CaptureFormat::Gif => {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("capture-frames-per-second"));
+                        ui.add(
+                            egui::DragValue::new(&mut self.frames_per_second).clamp_range(1..=100),
+                        );
+                    });
+                }
+                CaptureFormat::Video => self.video_settings_ui(ui),
+            }
+
+            if ui.button(tr!("capture-start")).clicked() {
+                self.start_with_settings();
+            }
+        }
+    }
+
+    /// Keeps a video dimension within the texture limit, and even since most encoders need that
+    fn clamp_size(&self, size: u32) -> u32 {
+        size.clamp(2, self.max_texture_size) & !1
+    }
+
+    fn video_settings_ui(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label(tr!("capture-codec"))
+            .selected_text(self.video.codec.name())
+            .show_ui(ui, |ui| {
+                for codec in [VideoCodec::H264, VideoCodec::ProRes] {
+                    ui.selectable_value(&mut self.video.codec, codec, codec.name());
+                }
+            });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("capture-resolution"));
+            ui.add(
+                egui::DragValue::new(&mut self.video.width)
+                    .clamp_range(2..=self.max_texture_size.min(7680))
+                    .prefix("w:"),
+            );
+            ui.add(
+                egui::DragValue::new(&mut self.video.height)
+                    .clamp_range(2..=self.max_texture_size.min(4320))
+                    .prefix("h:"),
+            );
+            self.video.width = self.clamp_size(self.video.width);
+            self.video.height = self.clamp_size(self.video.height);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("capture-frames-per-second"));
+            ui.add(egui::DragValue::new(&mut self.video.frames_per_second).clamp_range(1..=240));
+        });
+
+        if self.video.codec == VideoCodec::H264 {
+            ui.horizontal(|ui| {
+                ui.label(tr!("capture-bitrate"));
+                ui.add(
+                    egui::DragValue::new(&mut self.video.bitrate_kbps)
+                        .clamp_range(100..=200_000)
+                        .suffix(" kbps"),
+                );
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame_sink::MemorySink;
+
+    fn capture() -> Capture {
+        let ctx = egui::Context::default();
+        let diagnostics = Diagnostics::new(&ctx);
+        let jobs = Jobs::new(&ctx, diagnostics.clone());
+        Capture::new(diagnostics, jobs, 8192)
+    }
+
+    #[test]
+    fn writes_every_frame_to_the_sink() {
+        let mut capture = capture();
+        let sink = MemorySink::default();
+        capture.start(Box::new(sink.clone()), Some((2, 1)));
+        assert!(capture.is_active());
+        assert_eq!(capture.resolution(), Some((2, 1)));
+
+        let frames = (0..3u8)
+            .map(|i| Frame {
+                width: 2,
+                height: 1,
+                pixels: vec![i; 8],
+            })
+            .collect::<Vec<_>>();
+        for frame in &frames {
+            capture.write_frame(frame);
+        }
+        assert_eq!(capture.frames_written, frames.len());
+
+        let written = sink.frames.lock().unwrap();
+        assert_eq!(written.len(), frames.len());
+        for (written, frame) in written.iter().zip(&frames) {
+            assert_eq!((written.width, written.height), (frame.width, frame.height));
+            assert_eq!(written.pixels, frame.pixels);
+        }
+    }
+
+    #[test]
+    fn frames_are_ignored_without_a_capture() {
+        let mut capture = capture();
+        capture.write_frame(&Frame {
+            width: 1,
+            height: 1,
+            pixels: vec![0; 4],
+        });
+        assert!(!capture.is_active());
+        assert_eq!(capture.frames_written, 0);
+    }
+}
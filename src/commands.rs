@@ -0,0 +1,280 @@
+use crate::{bookmarks, localization::tr};
+use eframe::egui;
+
+/// An action that can be run from the command palette or a keyboard shortcut
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    CommandPalette,
+    CheatSheet,
+    ToggleCameraWindow,
+    ToggleCaptureWindow,
+    ToggleTimelineWindow,
+    PlayPauseTimeline,
+    StartStopCapture,
+    StartTutorial,
+    ToggleLogWindow,
+    ExportDiagnostics,
+    AboutGpu,
+    RenderStill,
+    /// Saves the camera into a numbered bookmark slot
+    StoreBookmark(usize),
+    /// Moves the camera to a numbered bookmark slot
+    RecallBookmark(usize),
+    #[cfg(feature = "renderdoc")]
+    CaptureRenderDocFrame,
+}
+
+impl Command {
+    pub const ALL: [Self; 12] = [
+        Self::CommandPalette,
+        Self::CheatSheet,
+        Self::ToggleCameraWindow,
+        Self::ToggleCaptureWindow,
+        Self::ToggleTimelineWindow,
+        Self::PlayPauseTimeline,
+        Self::StartStopCapture,
+        Self::StartTutorial,
+        Self::ToggleLogWindow,
+        Self::ExportDiagnostics,
+        Self::AboutGpu,
+        Self::RenderStill,
+    ];
+
+    /// Every command, including one of each bookmark command per slot and the ones behind features
+    pub fn all() -> impl Iterator<Item = Self> {
+        let commands = Self::ALL.into_iter().chain(
+            (0..bookmarks::KEYS.len())
+                .flat_map(|slot| [Self::StoreBookmark(slot), Self::RecallBookmark(slot)]),
+        );
+        #[cfg(feature = "renderdoc")]
+        let commands = commands.chain([Self::CaptureRenderDocFrame]);
+        commands
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::CommandPalette => tr!("command-palette"),
+            Self::CheatSheet => tr!("command-cheat-sheet"),
+            Self::ToggleCameraWindow => tr!("command-toggle-camera-window"),
+            Self::ToggleCaptureWindow => tr!("command-toggle-capture-window"),
+            Self::ToggleTimelineWindow => tr!("command-toggle-timeline-window"),
+            Self::PlayPauseTimeline => tr!("command-play-pause-timeline"),
+            Self::StartStopCapture => tr!("command-start-stop-capture"),
+            Self::StartTutorial => tr!("command-start-tutorial"),
+            Self::ToggleLogWindow => tr!("command-toggle-log-window"),
+            Self::ExportDiagnostics => tr!("command-export-diagnostics"),
+            Self::AboutGpu => tr!("command-about-gpu"),
+            Self::RenderStill => tr!("command-render-still"),
+            Self::StoreBookmark(slot) => tr!("command-store-bookmark", slot = slot + 1),
+            Self::RecallBookmark(slot) => tr!("command-recall-bookmark", slot = slot + 1),
+            #[cfg(feature = "renderdoc")]
+            Self::CaptureRenderDocFrame => tr!("renderdoc-capture"),
+        }
+    }
+
+    pub fn shortcut(self) -> Option<egui::KeyboardShortcut> {
+        use egui::{Key, KeyboardShortcut, Modifiers};
+
+        Some(match self {
+            Self::CommandPalette => KeyboardShortcut::new(Modifiers::COMMAND, Key::P),
+            Self::CheatSheet => KeyboardShortcut::new(Modifiers::NONE, Key::F1),
+            Self::ToggleCameraWindow => KeyboardShortcut::new(Modifiers::NONE, Key::F2),
+            Self::ToggleCaptureWindow => KeyboardShortcut::new(Modifiers::NONE, Key::F3),
+            Self::ToggleTimelineWindow => KeyboardShortcut::new(Modifiers::NONE, Key::F4),
+            Self::PlayPauseTimeline => KeyboardShortcut::new(Modifiers::COMMAND, Key::Space),
+            Self::StartStopCapture => KeyboardShortcut::new(Modifiers::NONE, Key::F9),
+            Self::ToggleLogWindow => KeyboardShortcut::new(Modifiers::NONE, Key::F12),
+            Self::StoreBookmark(slot) => {
+                KeyboardShortcut::new(Modifiers::COMMAND, bookmarks::KEYS[slot])
+            }
+            Self::RecallBookmark(slot) => {
+                KeyboardShortcut::new(Modifiers::NONE, bookmarks::KEYS[slot])
+            }
+            #[cfg(feature = "renderdoc")]
+            Self::CaptureRenderDocFrame => KeyboardShortcut::new(Modifiers::NONE, Key::F10),
+            Self::StartTutorial | Self::ExportDiagnostics | Self::AboutGpu | Self::RenderStill => {
+                return None
+            }
+        })
+    }
+
+    /// Returns every command whose shortcut was pressed this frame
+    pub fn pressed(ctx: &egui::Context) -> Vec<Self> {
+        // number keys typed into a text field shouldn't move the camera
+        let typing = ctx.wants_keyboard_input();
+        ctx.input_mut(|input| {
+            Self::all()
+                .filter(|command| !(typing && matches!(command, Self::RecallBookmark(_))))
+                .filter(|command| {
+                    command
+                        .shortcut()
+                        .is_some_and(|shortcut| input.consume_shortcut(&shortcut))
+                })
+                .collect()
+        })
+    }
+}
+
+/// Scores how well `query` matches `text` as a case insensitive subsequence, higher is better
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut text_chars = text.chars().flat_map(char::to_lowercase).enumerate();
+    let mut previous_index = None;
+    for query_char in query.chars().flat_map(char::to_lowercase) {
+        if query_char.is_whitespace() {
+            continue;
+        }
+        let (index, _) = text_chars.find(|&(_, c)| c == query_char)?;
+        // reward consecutive characters and matches near the start
+        score += match previous_index {
+            Some(previous) if previous + 1 == index => 5,
+            _ => 1,
+        };
+        if index == 0 {
+            score += 3;
+        }
+        previous_index = Some(index);
+    }
+    Some(score)
+}
+
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    /// Shows the palette if it is open, returning the command the user picked
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<Command> {
+        if !self.open {
+            return None;
+        }
+
+        let mut matches = Command::all()
+            .filter_map(|command| Some((fuzzy_score(&self.query, &command.name())?, command)))
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+
+        let (up, down, enter, escape) = ctx.input(|input| {
+            (
+                input.key_pressed(egui::Key::ArrowUp),
+                input.key_pressed(egui::Key::ArrowDown),
+                input.key_pressed(egui::Key::Enter),
+                input.key_pressed(egui::Key::Escape),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down {
+            self.selected += 1;
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut picked = None;
+        egui::Window::new(tr!("command-palette"))
+            .id(egui::Id::new("Command Palette"))
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 48.0))
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text(tr!("command-palette-hint"))
+                        .desired_width(320.0),
+                );
+                response.request_focus();
+                if response.changed() {
+                    self.selected = 0;
+                }
+
+                ui.separator();
+                for (i, &(_, command)) in matches.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .selectable_label(i == self.selected, command.name())
+                            .clicked()
+                        {
+                            picked = Some(command);
+                        }
+                        if let Some(shortcut) = command.shortcut() {
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.weak(ctx.format_shortcut(&shortcut));
+                                },
+                            );
+                        }
+                    });
+                }
+            });
+
+        if enter {
+            picked = picked.or(matches.get(self.selected).map(|&(_, command)| command));
+        }
+        if picked.is_some() || escape {
+            self.open = false;
+        }
+        picked
+    }
+}
+
+/// Lists every command that has a shortcut
+pub fn cheat_sheet_ui(ui: &mut egui::Ui) {
+    egui::Grid::new("Cheat Sheet").striped(true).show(ui, |ui| {
+        for command in Command::all() {
+            if let Some(shortcut) = command.shortcut() {
+                ui.label(command.name());
+                ui.monospace(ui.ctx().format_shortcut(&shortcut));
+                ui.end_row();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(fuzzy_score("", "Capture"), Some(0));
+    }
+
+    #[test]
+    fn characters_must_appear_in_order() {
+        assert_eq!(fuzzy_score("cpt", "Capture"), Some(10));
+        assert_eq!(fuzzy_score("tc", "Capture"), None);
+        assert_eq!(fuzzy_score("x", "Capture"), None);
+    }
+
+    #[test]
+    fn ignores_case_and_whitespace() {
+        assert_eq!(
+            fuzzy_score("C AP", "capture"),
+            fuzzy_score("cap", "Capture")
+        );
+    }
+
+    #[test]
+    fn prefers_consecutive_matches_at_the_start() {
+        assert_eq!(fuzzy_score("cap", "Capture"), Some(14));
+        assert_eq!(fuzzy_score("cap", "Command Palette"), Some(6));
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{diagnostics::Diagnostics, frame_sink::Frame, localization::tr};
+use eframe::egui;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CompareMode {
+    /// The reference is shown left of a draggable divider and the live render right of it
+    Wipe,
+    /// A heatmap of how much each pixel differs from the reference
+    Difference,
+}
+
+struct Reference {
+    frame: Frame,
+    texture: egui::TextureHandle,
+}
+
+/// Compares the live render against a snapshot or a loaded png
+pub struct Compare {
+    reference: Option<Reference>,
+    mode: CompareMode,
+    /// Where the wipe divider is, as a fraction of the viewport width
+    wipe: f32,
+    path: String,
+    snapshot_requested: bool,
+    difference: Option<egui::TextureHandle>,
+    /// Set when a new frame was rendered or the reference changed, the heatmap is only rebuilt then
+    difference_outdated: bool,
+    /// The largest per channel difference in the last heatmap, from 0 to 1
+    max_difference: f32,
+    diagnostics: Diagnostics,
+}
+
+impl Compare {
+    pub fn new(diagnostics: Diagnostics) -> Self {
+        Self {
+            reference: None,
+            mode: CompareMode::Wipe,
+            wipe: 0.5,
+            path: "reference.png".to_owned(),
+            snapshot_requested: false,
+            difference: None,
+            difference_outdated: false,
+            max_difference: 0.0,
+            diagnostics,
+        }
+    }
+
+    /// Whether the current frame has to be read back this frame, either for a snapshot or the difference heatmap
+    pub fn wants_frame(&self) -> bool {
+        self.snapshot_requested
+            || (self.reference.is_some()
+                && self.mode == CompareMode::Difference
+                && self.difference_outdated)
+    }
+
+    /// Called when the live render changed, so the heatmap gets rebuilt from the next frame
+    pub fn invalidate(&mut self) {
+        self.difference_outdated = true;
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context, frame: Frame) {
+        if std::mem::take(&mut self.snapshot_requested) {
+            self.set_reference(ctx, frame);
+            return;
+        }
+        let Some(reference) = &self.reference else {
+            return;
+        };
+        self.difference_outdated = false;
+        if (reference.frame.width, reference.frame.height) != (frame.width, frame.height) {
+            self.difference = None;
+            return;
+        }
+
+        let mut max_difference = 0;
+        let pixels = reference
+            .frame
+            .pixels
+            .chunks_exact(4)
+            .zip(frame.pixels.chunks_exact(4))
+            .map(|(a, b)| {
+                let difference = (0..3).map(|i| a[i].abs_diff(b[i])).max().unwrap();
+                max_difference = max_difference.max(difference);
+                heatmap(difference as f32 / 255.0)
+            })
+            .collect();
+        self.max_difference = max_difference as f32 / 255.0;
+
+        let image = egui::ColorImage {
+            size: [frame.width as _, frame.height as _],
+            pixels,
+        };
+        match &mut self.difference {
+            Some(texture) => texture.set(image, egui::TextureOptions::NEAREST),
+            None => {
+                self.difference = Some(ctx.load_texture(
+                    "Compare Difference",
+                    image,
+                    egui::TextureOptions::NEAREST,
+                ));
+            }
+        }
+    }
+
+    fn set_reference(&mut self, ctx: &egui::Context, frame: Frame) {
+        let texture = ctx.load_texture(
+            "Compare Reference",
+            egui::ColorImage::from_rgba_unmultiplied(
+                [frame.width as _, frame.height as _],
+                &frame.pixels,
+            ),
+            egui::TextureOptions::NEAREST,
+        );
+        self.reference = Some(Reference { frame, texture });
+        self.difference = None;
+        self.difference_outdated = true;
+    }
+
+    fn load(&mut self, ctx: &egui::Context) -> anyhow::Result<()> {
+        let image = image::open(&self.path)?.to_rgba8();
+        let frame = Frame {
+            width: image.width(),
+            height: image.height(),
+            pixels: image.into_raw(),
+        };
+        self.set_reference(ctx, frame);
+        Ok(())
+    }
+
+    /// Draws the comparison over the live render in `rect`
+    pub fn paint(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        let Some(reference) = &self.reference else {
+            return;
+        };
+        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        match self.mode {
+            CompareMode::Wipe => {
+                let divider = rect.left() + rect.width() * self.wipe;
+                let mut reference_rect = rect;
+                reference_rect.max.x = divider;
+                ui.painter().with_clip_rect(reference_rect).image(
+                    reference.texture.id(),
+                    rect,
+                    uv,
+                    egui::Color32::WHITE,
+                );
+
+                let handle =
+                    egui::Rect::from_x_y_ranges(divider - 4.0..=divider + 4.0, rect.y_range());
+                let response = ui
+                    .interact(handle, ui.id().with("Compare Wipe"), egui::Sense::drag())
+                    .on_hover_cursor(egui::CursorIcon::ResizeHorizontal);
+                if let Some(pointer) = response.interact_pointer_pos() {
+                    self.wipe = ((pointer.x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+                }
+                ui.painter().vline(
+                    divider,
+                    rect.y_range(),
+                    ui.visuals().widgets.active.fg_stroke,
+                );
+            }
+            CompareMode::Difference => {
+                if let Some(difference) = &self.difference {
+                    ui.painter()
+                        .image(difference.id(), rect, uv, egui::Color32::WHITE);
+                }
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button(tr!("compare-snapshot")).clicked() {
+                self.snapshot_requested = true;
+            }
+            if ui
+                .add_enabled(
+                    self.reference.is_some(),
+                    egui::Button::new(tr!("compare-clear")),
+                )
+                .clicked()
+            {
+                self.reference = None;
+                self.difference = None;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.path);
+            if ui.button(tr!("compare-load")).clicked() {
+                if let Err(error) = self.load(ui.ctx()) {
+                    self.diagnostics
+                        .report_error(&tr!("compare-load-failed"), &error);
+                }
+            }
+        });
+
+        let Some(reference) = &self.reference else {
+            ui.weak(tr!("compare-no-reference"));
+            return;
+        };
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.mode, CompareMode::Wipe, tr!("compare-wipe"));
+            ui.selectable_value(
+                &mut self.mode,
+                CompareMode::Difference,
+                tr!("compare-difference"),
+            );
+        });
+        match self.mode {
+            CompareMode::Wipe => {
+                ui.add(egui::Slider::new(&mut self.wipe, 0.0..=1.0).show_value(false));
+            }
+            CompareMode::Difference => {
+                if self.difference.is_some() {
+                    ui.label(tr!(
+                        "compare-max-difference",
+                        percent = format!("{:.1}", self.max_difference * 100.0)
+                    ));
+                } else {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr!(
+                            "compare-size-mismatch",
+                            width = reference.frame.width,
+                            height = reference.frame.height
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Maps 0..1 to black, red, yellow then white
+fn heatmap(t: f32) -> egui::Color32 {
+    let channel = |offset: f32| ((t * 3.0 - offset).clamp(0.0, 1.0) * 255.0) as u8;
+    egui::Color32::from_rgb(channel(0.0), channel(1.0), channel(2.0))
+}
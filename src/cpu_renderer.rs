@@ -0,0 +1,233 @@
+use crate::{
+    frame_sink::Frame, jobs::Progress, profiling::profile_scope, tweaks::Tweaks, GpuCamera,
+    GpuRenderSettings,
+};
+use cgmath::{vec2, vec3, ElementWise, Vector2, Vector3};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Tiles are the unit of work handed to rayon, which steals them between threads as they finish
+const TILE_SIZE: u32 = 64;
+/// The size of the blocks that share one sample in the first pass after the view changes, halved every frame
+const COARSEST_BLOCK_SIZE: u32 = 8;
+
+struct Tile {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    /// Each sample is stretched over a square block this big, 1 once the tile is fully refined
+    block_size: u32,
+    pixels: Vec<u8>,
+}
+
+/// Renders the same image as `shader.wgsl` on the CPU, for adapters that can't run the compute shader properly
+///
+/// After the view changes the image starts out blocky and is refined over the next few frames,
+/// so interaction stays responsive on large outputs
+pub struct CpuRenderer {
+    /// `Rgba8Unorm` pixels, ready to be uploaded to the output texture
+    pixels: Vec<u8>,
+    tiles: Vec<Tile>,
+    /// What the tiles were rendered with, refinement starts over when it changes
+    rendered: Option<(u32, u32, GpuCamera, GpuRenderSettings, Tweaks)>,
+}
+
+impl CpuRenderer {
+    pub fn new() -> Self {
+        Self {
+            pixels: vec![],
+            tiles: vec![],
+            rendered: None,
+        }
+    }
+
+    /// Whether some tiles still haven't been refined to full resolution
+    pub fn is_refining(&self) -> bool {
+        self.tiles.iter().any(|tile| tile.block_size > 1)
+    }
+
+    /// Renders the next refinement step, or the whole image at full resolution if `progressive` is false
+    pub fn render(
+        &mut self,
+        width: u32,
+        height: u32,
+        camera: &GpuCamera,
+        settings: &GpuRenderSettings,
+        tweaks: &Tweaks,
+        progressive: bool,
+    ) -> &[u8] {
+        profile_scope!("cpu render");
+        let view = (width, height, *camera, *settings, tweaks.clone());
+        if self.rendered.as_ref() != Some(&view) {
+            self.rendered = Some(view);
+            self.reset_tiles(width, height);
+        }
+
+        let sample = |x: u32, y: u32| sample(x, y, width, height, camera, settings, tweaks);
+
+        self.tiles
+            .par_iter_mut()
+            .filter(|tile| tile.block_size > 1)
+            .for_each(|tile| {
+                if !progressive {
+                    tile.block_size = 1;
+                }
+                tile.render(&sample);
+                tile.block_size = (tile.block_size / 2).max(1);
+            });
+
+        self.pixels.resize(width as usize * height as usize * 4, 0);
+        for tile in &self.tiles {
+            let row_bytes = tile.width as usize * 4;
+            for (row, tile_row) in tile.pixels.chunks_exact(row_bytes).enumerate() {
+                let start = ((tile.y as usize + row) * width as usize + tile.x as usize) * 4;
+                self.pixels[start..start + row_bytes].copy_from_slice(tile_row);
+            }
+        }
+        &self.pixels
+    }
+
+    fn reset_tiles(&mut self, width: u32, height: u32) {
+        self.tiles.clear();
+        for y in (0..height).step_by(TILE_SIZE as usize) {
+            for x in (0..width).step_by(TILE_SIZE as usize) {
+                self.tiles.push(Tile {
+                    x,
+                    y,
+                    width: TILE_SIZE.min(width - x),
+                    height: TILE_SIZE.min(height - y),
+                    block_size: COARSEST_BLOCK_SIZE,
+                    pixels: vec![],
+                });
+            }
+        }
+    }
+}
+
+/// Renders a whole image at full resolution, returning `None` if `progress` was cancelled part way through
+pub fn render_still(
+    width: u32,
+    height: u32,
+    camera: &GpuCamera,
+    settings: &GpuRenderSettings,
+    tweaks: &Tweaks,
+    progress: &Progress,
+) -> Option<Frame> {
+    let mut pixels = vec![0; width as usize * height as usize * 4];
+    let rows_done = AtomicU32::new(0);
+    pixels
+        .par_chunks_mut(width as usize * 4)
+        .enumerate()
+        .try_for_each(|(y, row)| {
+            if progress.is_cancelled() {
+                return None;
+            }
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                pixel.copy_from_slice(&sample(
+                    x as _, y as _, width, height, camera, settings, tweaks,
+                ));
+            }
+            let rows_done = rows_done.fetch_add(1, Ordering::Relaxed) + 1;
+            progress.set(rows_done as f32 / height as f32);
+            Some(())
+        })?;
+    Some(Frame {
+        width,
+        height,
+        pixels,
+    })
+}
+
+fn sample(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    camera: &GpuCamera,
+    settings: &GpuRenderSettings,
+    tweaks: &Tweaks,
+) -> [u8; 4] {
+    let aspect = width as f32 / height as f32;
+    let uv = vec2(x as f32 / width as f32, y as f32 / height as f32);
+    let world_position = (uv - vec2(0.5, 0.5))
+        .mul_element_wise(vec2(aspect * camera.height, camera.height))
+        + camera.position;
+    let pixel_size = camera.height / height as f32;
+    let color = debug_color(
+        world_position - camera.player_position,
+        settings.debug_palette,
+    ) * background_pattern(
+        world_position,
+        pixel_size,
+        settings.background_pattern,
+        tweaks,
+    );
+    [
+        (color.x * 255.0).round() as u8,
+        (color.y * 255.0).round() as u8,
+        (color.z * 255.0).round() as u8,
+        255,
+    ]
+}
+
+impl Tile {
+    fn render(&mut self, sample: &impl Fn(u32, u32) -> [u8; 4]) {
+        self.pixels
+            .resize(self.width as usize * self.height as usize * 4, 0);
+        let block_size = self.block_size;
+        for block_y in (0..self.height).step_by(block_size as usize) {
+            for block_x in (0..self.width).step_by(block_size as usize) {
+                // the whole block takes the sample from its top left corner
+                let color = sample(self.x + block_x, self.y + block_y);
+                for y in block_y..(block_y + block_size).min(self.height) {
+                    for x in block_x..(block_x + block_size).min(self.width) {
+                        let index = (y * self.width + x) as usize * 4;
+                        self.pixels[index..index + 4].copy_from_slice(&color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Must match `background_pattern` in the shader
+///
+/// The shader's defaults are used for tweaks it no longer declares
+fn background_pattern(
+    world_position: Vector2<f32>,
+    pixel_size: f32,
+    pattern: u32,
+    tweaks: &Tweaks,
+) -> f32 {
+    match pattern {
+        1 => {
+            let cell = world_position.x.floor() as i32 + world_position.y.floor() as i32;
+            if cell & 1 == 0 {
+                tweaks.get("checker_shade").unwrap_or(0.9)
+            } else {
+                1.0
+            }
+        }
+        2 => {
+            let distance = |value: f32| (value - value.round()).abs() / pixel_size;
+            let width = tweaks.get("grid_line_width").unwrap_or(1.0);
+            if distance(world_position.x).min(distance(world_position.y)) < width * 0.5 {
+                tweaks.get("grid_line_shade").unwrap_or(0.8)
+            } else {
+                1.0
+            }
+        }
+        _ => 1.0,
+    }
+}
+
+/// Must match `debug_color` in the shader
+fn debug_color(value: Vector2<f32>, debug_palette: u32) -> Vector3<f32> {
+    let v = vec2(value.x.clamp(0.0, 1.0), value.y.clamp(0.0, 1.0));
+    match debug_palette {
+        1 => v.x * vec3(0.902, 0.624, 0.0) + v.y * vec3(0.337, 0.706, 0.914),
+        2 => vec3(1.0, 1.0, 1.0) * ((v.x + v.y) * 0.5),
+        _ => vec3(v.x, v.y, 0.0),
+    }
+}
@@ -0,0 +1,156 @@
+use crate::localization::tr;
+use eframe::egui;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How long a message stays on screen as a toast
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// Older messages are dropped from the log once it gets this long
+const MAX_MESSAGES: usize = 1000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Error,
+}
+
+impl Severity {
+    fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            Self::Info => visuals.text_color(),
+            Self::Error => visuals.error_fg_color,
+        }
+    }
+}
+
+pub struct Message {
+    pub severity: Severity,
+    pub text: String,
+    pub time: Instant,
+}
+
+struct Inner {
+    start: Instant,
+    messages: Vec<Message>,
+    unread_errors: usize,
+}
+
+/// Collects problems from anywhere in the app so they can be shown to the user instead of printed to a console
+///
+/// Cloning gives another handle to the same log, so it can be moved into background threads
+#[derive(Clone)]
+pub struct Diagnostics {
+    inner: Arc<Mutex<Inner>>,
+    ctx: egui::Context,
+}
+
+impl Diagnostics {
+    pub fn new(ctx: &egui::Context) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                start: Instant::now(),
+                messages: vec![],
+                unread_errors: 0,
+            })),
+            ctx: ctx.clone(),
+        }
+    }
+
+    fn report(&self, severity: Severity, text: impl Into<String>) {
+        let text = text.into();
+        match severity {
+            Severity::Info => tracing::info!("{text}"),
+            Severity::Error => tracing::error!("{text}"),
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.messages.len() >= MAX_MESSAGES {
+            inner.messages.remove(0);
+        }
+        inner.messages.push(Message {
+            severity,
+            text,
+            time: Instant::now(),
+        });
+        if severity == Severity::Error {
+            inner.unread_errors += 1;
+        }
+        // messages can come from other threads while the app is idle
+        self.ctx.request_repaint();
+    }
+
+    pub fn info(&self, text: impl Into<String>) {
+        self.report(Severity::Info, text);
+    }
+
+    pub fn error(&self, text: impl Into<String>) {
+        self.report(Severity::Error, text);
+    }
+
+    /// Reports the error with its full chain of causes
+    pub fn report_error(&self, context: &str, error: &anyhow::Error) {
+        self.error(format!("{context}: {error:#}"));
+    }
+
+    pub fn unread_errors(&self) -> usize {
+        self.inner.lock().unwrap().unread_errors
+    }
+
+    /// Shows recent messages in the corner of the screen
+    pub fn show_toasts(&self, ctx: &egui::Context) {
+        let inner = self.inner.lock().unwrap();
+        let now = Instant::now();
+        let recent = inner
+            .messages
+            .iter()
+            .rev()
+            .take_while(|message| now - message.time < TOAST_DURATION)
+            .collect::<Vec<_>>();
+        if recent.is_empty() {
+            return;
+        }
+
+        egui::Area::new("Toasts")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                for message in recent.iter().rev() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(320.0);
+                        ui.colored_label(message.severity.color(ui.visuals()), &message.text);
+                    });
+                }
+            });
+
+        // redraw when the oldest toast expires so it disappears even if nothing else happens
+        let oldest = recent.last().unwrap();
+        ctx.request_repaint_after(TOAST_DURATION.saturating_sub(now - oldest.time));
+    }
+
+    /// Shows the full log, marking errors as read
+    pub fn log_ui(&self, ui: &mut egui::Ui) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.unread_errors = 0;
+
+        if ui.button(tr!("diagnostics-clear")).clicked() {
+            inner.messages.clear();
+        }
+        ui.separator();
+
+        let start = inner.start;
+        egui::ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                for message in &inner.messages {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.weak(format!("{:.1}s", (message.time - start).as_secs_f32()));
+                        ui.colored_label(message.severity.color(ui.visuals()), &message.text);
+                    });
+                }
+            });
+    }
+}
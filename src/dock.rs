@@ -0,0 +1,134 @@
+use crate::localization::tr;
+use egui_dock::{DockState, NodeIndex};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tab {
+    Viewport,
+    Scopes,
+    Compare,
+    Statistics,
+    Messages,
+    Logs,
+    Tasks,
+    Tweaks,
+    Camera,
+    Capture,
+    Timeline,
+    Bookmarks,
+    Annotations,
+    Settings,
+    GpuInfo,
+    #[cfg(feature = "midi")]
+    Midi,
+    #[cfg(feature = "osc")]
+    Osc,
+}
+
+impl Tab {
+    /// Where new tabs are docked, the feature gated ones are left out for simplicity
+    const PANELS: [Self; 14] = [
+        Self::Scopes,
+        Self::Compare,
+        Self::Statistics,
+        Self::Messages,
+        Self::Logs,
+        Self::Tasks,
+        Self::Tweaks,
+        Self::Camera,
+        Self::Capture,
+        Self::Timeline,
+        Self::Bookmarks,
+        Self::Annotations,
+        Self::Settings,
+        Self::GpuInfo,
+    ];
+
+    pub fn title(self) -> String {
+        match self {
+            Self::Viewport => tr!("viewport"),
+            Self::Scopes => tr!("scopes"),
+            Self::Compare => tr!("compare"),
+            Self::Statistics => tr!("statistics"),
+            Self::Messages => tr!("diagnostics"),
+            Self::Logs => tr!("logs"),
+            Self::Tasks => tr!("jobs"),
+            Self::Tweaks => tr!("tweaks"),
+            Self::Camera => tr!("camera"),
+            Self::Capture => tr!("capture"),
+            Self::Timeline => tr!("timeline"),
+            Self::Bookmarks => tr!("bookmarks"),
+            Self::Annotations => tr!("annotations"),
+            Self::Settings => tr!("settings"),
+            Self::GpuInfo => tr!("command-about-gpu"),
+            #[cfg(feature = "midi")]
+            Self::Midi => tr!("midi"),
+            #[cfg(feature = "osc")]
+            Self::Osc => tr!("osc"),
+        }
+    }
+}
+
+/// The viewport and the panels and tools docked around it, the layout is saved between runs
+pub struct Dock {
+    pub state: DockState<Tab>,
+}
+
+impl Dock {
+    const KEY: &'static str = "dock_layout";
+
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        Self {
+            state: storage
+                .and_then(|storage| eframe::get_value(storage, Self::KEY))
+                .unwrap_or_else(|| DockState::new(vec![Tab::Viewport])),
+        }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::KEY, &self.state);
+    }
+
+    pub fn is_open(&self, tab: Tab) -> bool {
+        self.state.find_tab(&tab).is_some()
+    }
+
+    /// Brings the tab to the front, adding it next to the other panels if it isn't open
+    pub fn open(&mut self, tab: Tab) {
+        if let Some(location) = self.state.find_tab(&tab) {
+            self.state.set_active_tab(location);
+            return;
+        }
+        // panels go next to each other so they don't cover the viewport
+        match Tab::PANELS
+            .iter()
+            .find_map(|panel| self.state.find_tab(panel))
+        {
+            Some((surface, node, _)) => self.state[surface][node].append_tab(tab),
+            None => {
+                self.state
+                    .main_surface_mut()
+                    .split_right(NodeIndex::root(), 0.7, vec![tab]);
+            }
+        }
+        if let Some(location) = self.state.find_tab(&tab) {
+            self.state.set_active_tab(location);
+        }
+    }
+
+    pub fn toggle(&mut self, tab: Tab) {
+        match self.state.find_tab(&tab) {
+            Some(location) => {
+                self.state.remove_tab(location);
+            }
+            None => self.open(tab),
+        }
+    }
+
+    /// Closes the tab if it's open
+    pub fn close(&mut self, tab: Tab) {
+        if let Some(location) = self.state.find_tab(&tab) {
+            self.state.remove_tab(location);
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use crate::profiling::profile_scope;
+use anyhow::Context;
+use eframe::wgpu;
+use image::codecs::gif::{GifEncoder, Repeat};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc,
+    thread::JoinHandle,
+};
+
+/// A completed frame in tightly packed RGBA8, with rows ordered from top to bottom
+#[derive(Clone)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Copies an `Rgba8Unorm` texture with `COPY_SRC` usage back to the CPU, blocking until the copy is done
+    #[tracing::instrument(skip_all, fields(width = texture.width(), height = texture.height()))]
+    pub fn read_texture(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+    ) -> anyhow::Result<Self> {
+        profile_scope!("read texture");
+        let (width, height) = (texture.width(), texture.height());
+        let unpadded_bytes_per_row = width * 4;
+        let padded_bytes_per_row = (unpadded_bytes_per_row + wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+            - 1)
+            / wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Readback Buffer"),
+            size: padded_bytes_per_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Frame Readback Command Encoder"),
+        });
+        command_encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        queue.submit([command_encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver.recv()??;
+
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            // the texture is displayed upside down, so flip it to match what is on screen
+            for row in data.chunks_exact(padded_bytes_per_row as usize).rev() {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        buffer.unmap();
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+/// Somewhere completed frames can be sent, such as an image sequence or video encoder
+pub trait FrameSink: Send {
+    fn write_frame(&mut self, frame: &Frame) -> anyhow::Result<()>;
+
+    /// Called after the last frame, to flush anything that is still buffered
+    fn finish(self: Box<Self>) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Keeps every frame in memory for tests, clones share the frames so they can be checked after the sink is handed off
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MemorySink {
+    pub frames: std::sync::Arc<std::sync::Mutex<Vec<Frame>>>,
+}
+
+#[cfg(test)]
+impl FrameSink for MemorySink {
+    fn write_frame(&mut self, frame: &Frame) -> anyhow::Result<()> {
+        self.frames.lock().unwrap().push(frame.clone());
+        Ok(())
+    }
+}
+
+/// Writes each frame as a numbered png file in a directory
+pub struct PngSequence {
+    directory: PathBuf,
+    next_index: usize,
+}
+
+impl PngSequence {
+    pub fn new(directory: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        std::fs::create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            next_index: 0,
+        })
+    }
+}
+
+impl FrameSink for PngSequence {
+    fn write_frame(&mut self, frame: &Frame) -> anyhow::Result<()> {
+        let path = self
+            .directory
+            .join(format!("frame_{:05}.png", self.next_index));
+        image::save_buffer(
+            path,
+            &frame.pixels,
+            frame.width,
+            frame.height,
+            image::ColorType::Rgba8,
+        )?;
+        self.next_index += 1;
+        Ok(())
+    }
+}
+
+/// Encodes every frame into a looping gif
+pub struct Gif {
+    encoder: GifEncoder<BufWriter<File>>,
+    frame_delay: image::Delay,
+}
+
+impl Gif {
+    pub fn new(path: impl AsRef<Path>, frames_per_second: u32) -> anyhow::Result<Self> {
+        let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+        encoder.set_repeat(Repeat::Infinite)?;
+        Ok(Self {
+            encoder,
+            frame_delay: image::Delay::from_numer_denom_ms(1000, frames_per_second.max(1)),
+        })
+    }
+}
+
+impl FrameSink for Gif {
+    fn write_frame(&mut self, frame: &Frame) -> anyhow::Result<()> {
+        let buffer = image::RgbaImage::from_raw(frame.width, frame.height, frame.pixels.clone())
+            .ok_or_else(|| anyhow::anyhow!("frame pixels do not match its size"))?;
+        self.encoder
+            .encode_frame(image::Frame::from_parts(buffer, 0, 0, self.frame_delay))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VideoCodec {
+    H264,
+    ProRes,
+}
+
+impl VideoCodec {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::H264 => "H.264",
+            Self::ProRes => "ProRes",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct VideoSettings {
+    pub width: u32,
+    pub height: u32,
+    pub frames_per_second: u32,
+    pub codec: VideoCodec,
+    /// Only used by H.264, ProRes picks its bitrate from the profile
+    pub bitrate_kbps: u32,
+}
+
+/// How many lines of ffmpeg's output are kept for error messages
+const FFMPEG_OUTPUT_LINES: usize = 10;
+
+/// Pipes raw frames into an `ffmpeg` process, which must be on the `PATH`
+pub struct Ffmpeg {
+    process: Child,
+    stdin: Option<ChildStdin>,
+    /// Drains ffmpeg's stderr so it never blocks on a full pipe, and returns the last lines once it exits
+    stderr: Option<JoinHandle<VecDeque<String>>>,
+    width: u32,
+    height: u32,
+}
+
+impl Ffmpeg {
+    pub fn new(path: impl AsRef<Path>, settings: VideoSettings) -> anyhow::Result<Self> {
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-y", "-nostats", "-f", "rawvideo", "-pix_fmt", "rgba", "-s"])
+            .arg(format!("{}x{}", settings.width, settings.height))
+            .arg("-r")
+            .arg(settings.frames_per_second.to_string())
+            .args(["-i", "-"]);
+        match settings.codec {
+            VideoCodec::H264 => command
+                .args(["-c:v", "libx264", "-pix_fmt", "yuv420p", "-b:v"])
+                .arg(format!("{}k", settings.bitrate_kbps)),
+            VideoCodec::ProRes => command.args([
+                "-c:v",
+                "prores_ks",
+                "-profile:v",
+                "3",
+                "-pix_fmt",
+                "yuv422p10le",
+            ]),
+        };
+        let mut process = command
+            .arg(path.as_ref())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("failed to start ffmpeg, is it installed?")?;
+
+        let stderr = process.stderr.take().map(|stderr| {
+            std::thread::spawn(move || {
+                let mut lines = VecDeque::with_capacity(FFMPEG_OUTPUT_LINES);
+                for line in BufReader::new(stderr).lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    if lines.len() == FFMPEG_OUTPUT_LINES {
+                        lines.pop_front();
+                    }
+                    lines.push_back(line);
+                }
+                lines
+            })
+        });
+
+        Ok(Self {
+            stdin: process.stdin.take(),
+            stderr,
+            process,
+            width: settings.width,
+            height: settings.height,
+        })
+    }
+
+    /// The last lines ffmpeg wrote to stderr, only call this once the process has exited
+    fn output(&mut self) -> String {
+        let lines = self
+            .stderr
+            .take()
+            .and_then(|stderr| stderr.join().ok())
+            .unwrap_or_default();
+        if lines.is_empty() {
+            "ffmpeg printed nothing".to_owned()
+        } else {
+            Vec::from(lines).join("\n")
+        }
+    }
+}
+
+impl FrameSink for Ffmpeg {
+    fn write_frame(&mut self, frame: &Frame) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            frame.width == self.width && frame.height == self.height,
+            "frame size {}x{} does not match the video size {}x{}",
+            frame.width,
+            frame.height,
+            self.width,
+            self.height,
+        );
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("ffmpeg stdin is closed"))?;
+        if let Err(error) = stdin.write_all(&frame.pixels) {
+            // the pipe usually breaks because ffmpeg exited, its output says why
+            drop(self.stdin.take());
+            self.process.wait()?;
+            return Err(error).context(self.output());
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> anyhow::Result<()> {
+        // closing stdin tells ffmpeg there are no more frames
+        drop(self.stdin.take());
+        let status = self.process.wait()?;
+        anyhow::ensure!(
+            status.success(),
+            "ffmpeg exited with {status}: {}",
+            self.output()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(width: u32, height: u32, seed: u8) -> Frame {
+        Frame {
+            width,
+            height,
+            pixels: (0..width * height * 4)
+                .map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn png_sequence_round_trips() {
+        let directory =
+            std::env::temp_dir().join(format!("raytracing_2d_png_sequence_{}", std::process::id()));
+        let frames = [frame(3, 2, 0), frame(3, 2, 1)];
+        let mut sink = Box::new(PngSequence::new(&directory).unwrap());
+        for frame in &frames {
+            sink.write_frame(frame).unwrap();
+        }
+        sink.finish().unwrap();
+
+        for (index, frame) in frames.iter().enumerate() {
+            let image = image::open(directory.join(format!("frame_{index:05}.png")))
+                .unwrap()
+                .to_rgba8();
+            assert_eq!(image.dimensions(), (frame.width, frame.height));
+            assert_eq!(image.into_raw(), frame.pixels);
+        }
+        assert!(!directory.join("frame_00002.png").exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+use crate::localization::tr;
+use eframe::{egui, wgpu};
+
+/// The `@workgroup_size` of the compute shaders, the same size `build.rs` checks them against
+const COMPUTE_WORKGROUP_SIZE: [u32; 3] = [16, 16, 1];
+
+/// What the adapter and device can do, queried once at startup
+pub struct GpuInfo {
+    pub adapter: wgpu::AdapterInfo,
+    pub features: wgpu::Features,
+    pub limits: wgpu::Limits,
+    pub downlevel: wgpu::DownlevelCapabilities,
+}
+
+impl GpuInfo {
+    pub fn new(adapter: &wgpu::Adapter, device: &wgpu::Device) -> Self {
+        Self {
+            adapter: adapter.get_info(),
+            features: device.features(),
+            limits: device.limits(),
+            downlevel: adapter.get_downlevel_capabilities(),
+        }
+    }
+
+    /// The renderer writes its output from a compute shader, which some downlevel backends can't run
+    ///
+    /// The device limits are checked too, the GL backend creates its device with the WebGL2 limits,
+    /// which don't allow any compute workgroups or storage textures even when the adapter could
+    pub fn supports_compute(&self) -> bool {
+        let [width, height, depth] = COMPUTE_WORKGROUP_SIZE;
+        self.downlevel
+            .flags
+            .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+            && self.limits.max_compute_invocations_per_workgroup >= width * height * depth
+            && self.limits.max_compute_workgroup_size_x >= width
+            && self.limits.max_compute_workgroup_size_y >= height
+            && self.limits.max_compute_workgroup_size_z >= depth
+            && self.limits.max_storage_textures_per_shader_stage >= 1
+    }
+
+    /// The largest width or height the output texture can have
+    pub fn max_texture_size(&self) -> u32 {
+        self.limits.max_texture_dimension_2d
+    }
+
+    /// Plain text version of everything shown in the window, for diagnostics bundles
+    pub fn report(&self) -> String {
+        format!(
+            "[adapter]\n{:#?}\n\n[features]\n{:?}\n\n[downlevel]\n{:?}\n\n[limits]\n{:#?}\n",
+            self.adapter, self.features, self.downlevel, self.limits,
+        )
+    }
+
+    pub fn ui(&self, ui: &mut egui::Ui) {
+        egui::Grid::new("GPU Info").show(ui, |ui| {
+            ui.label(tr!("gpu-info-name"));
+            ui.label(&self.adapter.name);
+            ui.end_row();
+
+            ui.label(tr!("gpu-info-backend"));
+            ui.label(format!("{:?}", self.adapter.backend));
+            ui.end_row();
+
+            ui.label(tr!("gpu-info-device-type"));
+            ui.label(format!("{:?}", self.adapter.device_type));
+            ui.end_row();
+
+            ui.label(tr!("gpu-info-driver"));
+            ui.label(format!(
+                "{} {}",
+                self.adapter.driver, self.adapter.driver_info
+            ));
+            ui.end_row();
+
+            ui.label(tr!("gpu-info-compute"));
+            ui.label(if self.supports_compute() {
+                tr!("gpu-info-supported")
+            } else {
+                tr!("gpu-info-unsupported")
+            });
+            ui.end_row();
+
+            ui.label(tr!("gpu-info-max-texture-size"));
+            ui.label(self.max_texture_size().to_string());
+            ui.end_row();
+        });
+
+        ui.collapsing(tr!("gpu-info-features"), |ui| {
+            ui.monospace(format!("{:?}", self.features));
+        });
+        ui.collapsing(tr!("gpu-info-limits"), |ui| {
+            ui.monospace(format!("{:#?}", self.limits));
+        });
+    }
+}
@@ -0,0 +1,233 @@
+#[cfg(feature = "tracy")]
+use crate::gpu_zones::GpuZones;
+use crate::{
+    permutations::ShaderPermutations, profiling::profile_scope, reflection, tweaks, GpuCamera,
+    GpuRenderSettings,
+};
+use eframe::wgpu;
+use encase::{ShaderSize, UniformBuffer};
+
+/// Renders `shader.wgsl` into the output texture with a compute pipeline
+///
+/// Only created when the adapter supports compute shaders, the CPU renderer is used otherwise
+pub struct GpuRenderer {
+    output_texture_bind_group_layout: wgpu::BindGroupLayout,
+    output_texture_bind_group: wgpu::BindGroup,
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    tweaks_buffer: wgpu::Buffer,
+    tweaks_bind_group: wgpu::BindGroup,
+    pipelines: ShaderPermutations,
+    /// What the bind group layouts were created from, a reloaded shader has to keep using the same bindings
+    bind_group_entries: Vec<Vec<wgpu::BindGroupLayoutEntry>>,
+    /// Uploads the uniforms every frame without allocating a new staging buffer each time
+    staging_belt: wgpu::util::StagingBelt,
+    #[cfg(feature = "tracy")]
+    gpu_zones: Option<GpuZones>,
+}
+
+impl GpuRenderer {
+    pub fn new(
+        render_state: &eframe::egui_wgpu::RenderState,
+        output_texture: &wgpu::Texture,
+    ) -> Self {
+        let device = &render_state.device;
+        let mut bind_group_layouts =
+            reflection::bind_group_layouts(device, "Compute", include_str!("./shader.wgsl"))
+                .unwrap()
+                .into_iter();
+        let output_texture_bind_group_layout = bind_group_layouts.next().unwrap();
+        let camera_bind_group_layout = bind_group_layouts.next().unwrap();
+        let tweaks_bind_group_layout = bind_group_layouts.next().unwrap();
+
+        let output_texture_bind_group =
+            output_texture_bind_group(device, &output_texture_bind_group_layout, output_texture);
+
+        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Camera Buffer"),
+            size: <GpuCamera as ShaderSize>::SHADER_SIZE.get(),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            mapped_at_creation: false,
+        });
+
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Camera Bind Group"),
+            layout: &camera_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera_buffer.as_entire_binding(),
+            }],
+        });
+
+        let tweaks_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Tweaks Buffer"),
+            size: tweaks::BUFFER_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            mapped_at_creation: false,
+        });
+
+        let tweaks_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Tweaks Bind Group"),
+            layout: &tweaks_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: tweaks_buffer.as_entire_binding(),
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Compute Pipeline Layout"),
+            bind_group_layouts: &[
+                &output_texture_bind_group_layout,
+                &camera_bind_group_layout,
+                &tweaks_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        Self {
+            output_texture_bind_group_layout,
+            output_texture_bind_group,
+            camera_buffer,
+            camera_bind_group,
+            tweaks_buffer,
+            tweaks_bind_group,
+            bind_group_entries: reflection::bind_group_entries(
+                "Compute",
+                include_str!("./shader.wgsl"),
+            )
+            .unwrap(),
+            pipelines: ShaderPermutations::new(
+                device,
+                "Compute Pipeline",
+                pipeline_layout,
+                include_str!("./shader.wgsl").to_owned(),
+                GpuRenderSettings::all_permutations(),
+            ),
+            // enough for all the uniforms in a frame, so the belt only needs one chunk
+            staging_belt: wgpu::util::StagingBelt::new(1024),
+            #[cfg(feature = "tracy")]
+            gpu_zones: GpuZones::new(device, &render_state.queue),
+        }
+    }
+
+    /// Must be called whenever the output texture is recreated
+    pub fn set_output_texture(&mut self, device: &wgpu::Device, output_texture: &wgpu::Texture) {
+        self.output_texture_bind_group = output_texture_bind_group(
+            device,
+            &self.output_texture_bind_group_layout,
+            output_texture,
+        );
+    }
+
+    /// Switches to a reloaded shader, keeping the old one if the new one can't be used
+    pub fn set_source(&mut self, device: &wgpu::Device, source: String) -> anyhow::Result<()> {
+        if reflection::bind_group_entries("Compute", &source)? != self.bind_group_entries {
+            anyhow::bail!("the shader's bindings changed, restart to use them");
+        }
+        self.pipelines.set_source(device, source)
+    }
+
+    /// How many shader permutations are compiled
+    pub fn compiled_permutations(&self) -> usize {
+        self.pipelines.compiled()
+    }
+
+    pub fn uniform_buffers_size(&self) -> u64 {
+        self.camera_buffer.size() + self.tweaks_buffer.size()
+    }
+
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        output_texture: &wgpu::Texture,
+        camera: &GpuCamera,
+        settings: &GpuRenderSettings,
+        tweaks: &tweaks::Tweaks,
+    ) {
+        let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Compute Command Encoder"),
+        });
+
+        // Upload camera uniform
+        {
+            profile_scope!("upload camera");
+            let mut buffer =
+                UniformBuffer::new([0; <GpuCamera as ShaderSize>::SHADER_SIZE.get() as _]);
+            buffer.write(camera).unwrap();
+            self.staging_belt
+                .write_buffer(
+                    &mut command_encoder,
+                    &self.camera_buffer,
+                    0,
+                    <GpuCamera as ShaderSize>::SHADER_SIZE,
+                    device,
+                )
+                .copy_from_slice(&buffer.into_inner());
+        }
+
+        // Upload tweaks uniform
+        let tweaks = tweaks.bytes();
+        if let Some(size) = wgpu::BufferSize::new(tweaks.len() as _) {
+            profile_scope!("upload tweaks");
+            self.staging_belt
+                .write_buffer(&mut command_encoder, &self.tweaks_buffer, 0, size, device)
+                .copy_from_slice(&tweaks);
+        }
+
+        self.staging_belt.finish();
+
+        #[cfg(feature = "tracy")]
+        if let Some(gpu_zones) = &mut self.gpu_zones {
+            gpu_zones.begin(&mut command_encoder);
+        }
+        {
+            profile_scope!("dispatch");
+            let mut compute_pass =
+                command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("Compute Pass"),
+                });
+
+            let (workgroup_width, workgroup_height) = (16, 16);
+            let (workgroups_x, workgroups_y) = (
+                (output_texture.width() + workgroup_width - 1) / workgroup_width,
+                (output_texture.height() + workgroup_height - 1) / workgroup_height,
+            );
+
+            compute_pass.set_pipeline(self.pipelines.get(settings.permutation()));
+            compute_pass.set_bind_group(0, &self.output_texture_bind_group, &[]);
+            compute_pass.set_bind_group(1, &self.camera_bind_group, &[]);
+            compute_pass.set_bind_group(2, &self.tweaks_bind_group, &[]);
+            compute_pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
+        }
+        #[cfg(feature = "tracy")]
+        if let Some(gpu_zones) = &mut self.gpu_zones {
+            gpu_zones.end(&mut command_encoder, "render");
+        }
+        queue.submit([command_encoder.finish()]);
+        #[cfg(feature = "tracy")]
+        if let Some(gpu_zones) = &mut self.gpu_zones {
+            gpu_zones.collect();
+        }
+        // the chunks can only be reused once the gpu is done copying out of them
+        self.staging_belt.recall();
+    }
+}
+
+fn output_texture_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    output_texture: &wgpu::Texture,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Output Texture Bind Group"),
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::TextureView(
+                &output_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            ),
+        }],
+    })
+}
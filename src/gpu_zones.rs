@@ -0,0 +1,139 @@
+use eframe::wgpu;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc,
+};
+
+/// Timestamps written around GPU work and sent to Tracy as GPU zones
+///
+/// Results are read back asynchronously, so while one zone is in flight new ones are skipped
+pub struct GpuZones {
+    context: tracy_client::GpuContext,
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    in_flight: Option<(tracy_client::GpuSpan, Arc<AtomicBool>)>,
+    recording: bool,
+}
+
+impl GpuZones {
+    /// Returns `None` if the device wasn't created with timestamp queries or Tracy isn't running
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+        let client = tracy_client::Client::running()?;
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Tracy Query Set"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Tracy Resolve Buffer"),
+            size: 16,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Tracy Readback Buffer"),
+            size: 16,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        // tracy needs a gpu timestamp from around now to line the gpu timeline up with the cpu one
+        let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Tracy Calibration Command Encoder"),
+        });
+        command_encoder.write_timestamp(&query_set, 0);
+        command_encoder.resolve_query_set(&query_set, 0..1, &resolve_buffer, 0);
+        command_encoder.copy_buffer_to_buffer(&resolve_buffer, 0, &readback_buffer, 0, 8);
+        queue.submit([command_encoder.finish()]);
+        let slice = readback_buffer.slice(..8);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver.recv().ok()?.ok()?;
+        let timestamp = u64::from_le_bytes(slice.get_mapped_range()[..8].try_into().unwrap());
+        readback_buffer.unmap();
+
+        let context = client
+            .new_gpu_context(
+                Some("wgpu"),
+                tracy_client::GpuContextType::Invalid,
+                timestamp as i64,
+                queue.get_timestamp_period(),
+            )
+            .ok()?;
+
+        Some(Self {
+            context,
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            in_flight: None,
+            recording: false,
+        })
+    }
+
+    /// Starts a zone, skipped if the previous one hasn't been read back yet
+    pub fn begin(&mut self, command_encoder: &mut wgpu::CommandEncoder) {
+        self.recording = self.in_flight.is_none();
+        if self.recording {
+            command_encoder.write_timestamp(&self.query_set, 0);
+        }
+    }
+
+    /// Ends the zone started by [`Self::begin`], the command encoder must be submitted before calling [`Self::collect`]
+    pub fn end(&mut self, command_encoder: &mut wgpu::CommandEncoder, name: &str) {
+        if !std::mem::take(&mut self.recording) {
+            return;
+        }
+        command_encoder.write_timestamp(&self.query_set, 1);
+        command_encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        command_encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            16,
+        );
+        let Ok(mut span) = self.context.span_alloc(name, "", file!(), line!()) else {
+            return;
+        };
+        span.end_zone();
+        self.in_flight = Some((span, Arc::new(AtomicBool::new(false))));
+    }
+
+    /// Maps the readback buffer after submission and sends finished zones to Tracy
+    pub fn collect(&mut self) {
+        let Some((_, mapped)) = &self.in_flight else {
+            return;
+        };
+        if !mapped.load(Ordering::Acquire) {
+            // the map callback holds the only other reference until it has run
+            if Arc::strong_count(mapped) == 1 {
+                let mapped = mapped.clone();
+                self.readback_buffer
+                    .slice(..)
+                    .map_async(wgpu::MapMode::Read, move |result| {
+                        mapped.store(result.is_ok(), Ordering::Release);
+                    });
+            }
+            return;
+        }
+
+        let (start, end) = {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            let timestamp =
+                |i: usize| u64::from_le_bytes(data[i * 8..i * 8 + 8].try_into().unwrap()) as i64;
+            (timestamp(0), timestamp(1))
+        };
+        self.readback_buffer.unmap();
+        let (span, _) = self.in_flight.take().unwrap();
+        span.upload_timestamp(start, end);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{diagnostics::Diagnostics, localization::tr};
+use eframe::egui;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// Shared between a job and the tasks window
+pub struct Progress {
+    cancelled: AtomicBool,
+    /// The bits of an `f32` from 0 to 1, or `u32::MAX` when the job can't tell how far along it is
+    fraction: AtomicU32,
+}
+
+impl Progress {
+    /// Jobs should check this regularly and return early once it's set
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, fraction: f32) {
+        self.fraction
+            .store(fraction.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    fn fraction(&self) -> Option<f32> {
+        let bits = self.fraction.load(Ordering::Relaxed);
+        (bits != u32::MAX).then(|| f32::from_bits(bits))
+    }
+}
+
+struct Job {
+    name: String,
+    cancelable: bool,
+    progress: Arc<Progress>,
+    handle: JoinHandle<anyhow::Result<()>>,
+}
+
+/// Runs long operations on background threads so they don't block the frame loop
+///
+/// Cloning gives another handle to the same list of jobs
+#[derive(Clone)]
+pub struct Jobs {
+    jobs: Arc<Mutex<Vec<Job>>>,
+    diagnostics: Diagnostics,
+    ctx: egui::Context,
+}
+
+impl Jobs {
+    pub fn new(ctx: &egui::Context, diagnostics: Diagnostics) -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(vec![])),
+            diagnostics,
+            ctx: ctx.clone(),
+        }
+    }
+
+    /// Starts `job` on its own thread, errors it returns are reported to the user
+    pub fn spawn(
+        &self,
+        name: String,
+        cancelable: bool,
+        job: impl FnOnce(&Progress) -> anyhow::Result<()> + Send + 'static,
+    ) {
+        let progress = Arc::new(Progress {
+            cancelled: AtomicBool::new(false),
+            fraction: AtomicU32::new(u32::MAX),
+        });
+        let spawned = thread::Builder::new().name(name.clone()).spawn({
+            let progress = progress.clone();
+            let ctx = self.ctx.clone();
+            move || {
+                let result = job(&progress);
+                // let the ui notice the job finished
+                ctx.request_repaint();
+                result
+            }
+        });
+        match spawned {
+            Ok(handle) => self.jobs.lock().unwrap().push(Job {
+                name,
+                cancelable,
+                progress,
+                handle,
+            }),
+            Err(error) => self
+                .diagnostics
+                .report_error(&tr!("job-failed", name = name), &error.into()),
+        }
+    }
+
+    pub fn running(&self) -> usize {
+        self.jobs.lock().unwrap().len()
+    }
+
+    /// Asks every job that can be cancelled to stop, the rest are left running
+    pub fn cancel_all(&self) {
+        for job in self
+            .jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|job| job.cancelable)
+        {
+            job.progress.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Blocks until every running job has finished, used when the app is closing
+    pub fn wait(&self) {
+        while self.running() > 0 {
+            self.update();
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    /// Removes finished jobs, reporting any that failed, called once per frame
+    pub fn update(&self) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let (finished, running) = std::mem::take(&mut *jobs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|job| job.handle.is_finished());
+        *jobs = running;
+        drop(jobs);
+
+        for job in finished {
+            let result = job
+                .handle
+                .join()
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("the job panicked")));
+            if let Err(error) = result {
+                self.diagnostics
+                    .report_error(&tr!("job-failed", name = job.name), &error);
+            }
+        }
+    }
+
+    pub fn ui(&self, ui: &mut egui::Ui) {
+        let jobs = self.jobs.lock().unwrap();
+        if jobs.is_empty() {
+            ui.weak(tr!("jobs-none"));
+            return;
+        }
+        for job in jobs.iter() {
+            ui.horizontal(|ui| {
+                ui.label(&job.name);
+                match job.progress.fraction() {
+                    Some(fraction) => {
+                        ui.add(egui::ProgressBar::new(fraction).show_percentage());
+                    }
+                    None => {
+                        ui.spinner();
+                    }
+                }
+                if job.cancelable {
+                    let cancelled = job.progress.is_cancelled();
+                    if ui
+                        .add_enabled(!cancelled, egui::Button::new(tr!("jobs-cancel")))
+                        .clicked()
+                    {
+                        job.progress.cancelled.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+        // progress bars don't animate by themselves
+        ui.ctx()
+            .request_repaint_after(std::time::Duration::from_millis(100));
+    }
+}
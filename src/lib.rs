@@ -1,289 +1,1318 @@
-use eframe::{
-    egui,
-    wgpu::{self, include_wgsl},
-};
-use encase::{ShaderSize, ShaderType, UniformBuffer};
+use commands::Command;
+use eframe::{egui, wgpu};
+use egui_dock::{DockArea, DockState};
+use encase::ShaderType;
+use localization::tr;
+use parameters::Parameter;
+use profiling::profile_scope;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
-#[derive(ShaderType)]
+mod annotations;
+mod autosave;
+mod bookmarks;
+mod bug_report;
+mod capture;
+mod commands;
+mod compare;
+mod cpu_renderer;
+mod diagnostics;
+mod dock;
+pub mod frame_sink;
+mod gpu_info;
+mod gpu_renderer;
+#[cfg(feature = "tracy")]
+mod gpu_zones;
+mod jobs;
+mod localization;
+pub mod logging;
+mod parameters;
+mod permutations;
+mod profiling;
+mod reflection;
+#[cfg(any(feature = "http-api", feature = "midi", feature = "osc"))]
+mod remote;
+#[cfg(feature = "renderdoc")]
+pub mod renderdoc_capture;
+mod scopes;
+mod settings;
+mod shader_reload;
+mod theme;
+mod time_control;
+mod timeline;
+mod transition;
+mod tutorial;
+mod tweaks;
+
+const TUTORIAL_COMPLETED_KEY: &str = "tutorial_completed";
+const STILL_PATH: &str = "still.png";
+/// The longest time step animations advance by in one frame
+const MAX_TIME_STEP: f32 = 0.1;
+
+#[derive(Clone, Copy, PartialEq, ShaderType, Serialize, Deserialize)]
 struct GpuCamera {
     position: cgmath::Vector2<f32>,
     height: f32,
     player_position: cgmath::Vector2<f32>,
 }
 
+impl GpuCamera {
+    fn parameter(&self, parameter: Parameter) -> f32 {
+        match parameter {
+            Parameter::CameraX => self.position.x,
+            Parameter::CameraY => self.position.y,
+            Parameter::CameraHeight => self.height,
+            Parameter::PlayerX => self.player_position.x,
+            Parameter::PlayerY => self.player_position.y,
+        }
+    }
+
+    fn pose(&self) -> timeline::CameraPose {
+        timeline::CameraPose {
+            position: self.position,
+            height: self.height,
+        }
+    }
+
+    fn set_pose(&mut self, pose: timeline::CameraPose) {
+        self.position = pose.position;
+        self.height = pose.height;
+    }
+
+    fn parameter_mut(&mut self, parameter: Parameter) -> &mut f32 {
+        match parameter {
+            Parameter::CameraX => &mut self.position.x,
+            Parameter::CameraY => &mut self.position.y,
+            Parameter::CameraHeight => &mut self.height,
+            Parameter::PlayerX => &mut self.player_position.x,
+            Parameter::PlayerY => &mut self.player_position.y,
+        }
+    }
+}
+
+/// The editor state that is autosaved and restored after a crash
+#[derive(Serialize, Deserialize)]
+struct Session {
+    camera: GpuCamera,
+    timeline: timeline::Timeline,
+    // sessions autosaved before annotations existed don't have any
+    #[serde(default)]
+    annotations: annotations::Annotations,
+}
+
+/// Baked into the shader as a pipeline permutation rather than uploaded as a uniform
+#[derive(Clone, Copy, PartialEq)]
+struct GpuRenderSettings {
+    debug_palette: u32,
+    background_pattern: u32,
+}
+
+impl GpuRenderSettings {
+    fn permutation(&self) -> permutations::Permutation {
+        [
+            ("DEBUG_PALETTE", self.debug_palette),
+            ("BACKGROUND_PATTERN", self.background_pattern),
+        ]
+    }
+
+    /// Every combination of settings the shader can be specialized for
+    fn all_permutations() -> Vec<permutations::Permutation> {
+        settings::DebugPalette::ALL
+            .into_iter()
+            .flat_map(|palette| {
+                settings::BackgroundPattern::ALL
+                    .into_iter()
+                    .map(move |pattern| {
+                        GpuRenderSettings {
+                            debug_palette: palette.gpu_index(),
+                            background_pattern: pattern.gpu_index(),
+                        }
+                        .permutation()
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Which renderer draws the scene, chosen with `--renderer` on the command line
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum RendererKind {
+    #[default]
+    Gpu,
+    /// Slower, but works on adapters without compute shader support
+    Cpu,
+}
+
+impl std::str::FromStr for RendererKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gpu" => Ok(Self::Gpu),
+            "cpu" => Ok(Self::Cpu),
+            _ => anyhow::bail!("unknown renderer '{s}', expected gpu or cpu"),
+        }
+    }
+}
+
+/// The renderer picked by [`RendererKind`], only the one in use creates its GPU resources
+enum Renderer {
+    Gpu(gpu_renderer::GpuRenderer),
+    Cpu(cpu_renderer::CpuRenderer),
+}
+
 pub struct App {
     egui_texture_id: egui::TextureId,
     main_texture: wgpu::Texture,
-    output_texture_bind_group_layout: wgpu::BindGroupLayout,
-    output_texture_bind_group: wgpu::BindGroup,
     camera: GpuCamera,
-    camera_buffer: wgpu::Buffer,
-    camera_bind_group: wgpu::BindGroup,
-    compute_pipeline: wgpu::ComputePipeline,
-    camera_window: bool,
+    tweaks: tweaks::Tweaks,
+    renderer: Renderer,
+    /// Replaces the compute pipeline when `shader.wgsl` is edited, the last good pipeline is kept if it doesn't compile
+    shader_reload: shader_reload::ShaderReload,
+    diagnostics: diagnostics::Diagnostics,
+    jobs: jobs::Jobs,
+    capture: capture::Capture,
+    timeline: timeline::Timeline,
+    compare: compare::Compare,
+    bookmarks: bookmarks::Bookmarks,
+    annotations: annotations::Annotations,
+    camera_transition: Option<transition::CameraTransition>,
+    time_control: time_control::TimeControl,
+    /// Where the rendered image was drawn last frame
+    viewport: egui::Rect,
+    /// When the user last moved the camera, the preview resolution is used until input goes idle
+    camera_interaction_instant: Option<Instant>,
+    /// What the output texture holds, rendering is skipped while nothing that affects the image has changed
+    rendered_view: Option<(GpuCamera, GpuRenderSettings, tweaks::Tweaks)>,
+    dock: dock::Dock,
+    /// Only available with the GPU renderer, the scopes are computed with a compute shader
+    scopes: Option<scopes::Scopes>,
+    tutorial: tutorial::Tutorial,
+    command_palette: commands::CommandPalette,
+    settings: settings::Settings,
+    cheat_sheet_window: bool,
+    logs: logging::Logs,
+    bug_report: bug_report::BugReport,
+    gpu_info: gpu_info::GpuInfo,
+    autosave: autosave::Autosave,
+    /// A session left behind by a crash, waiting for the user to restore or discard it
+    recovered_session: Option<Session>,
+    last_frame_instant: Instant,
+    last_frame_time: Duration,
+    #[cfg(feature = "http-api")]
+    remote_commands: Option<std::sync::mpsc::Receiver<remote::http::RemoteCommand>>,
+    #[cfg(feature = "midi")]
+    midi: remote::midi::Midi,
+    #[cfg(feature = "osc")]
+    osc: remote::osc::Osc,
+    #[cfg(feature = "puffin")]
+    profiler_window: bool,
 }
 
 impl App {
-    pub fn new(cc: &eframe::CreationContext) -> Self {
+    pub fn new(
+        cc: &eframe::CreationContext,
+        logs: logging::Logs,
+        renderer_kind: RendererKind,
+    ) -> Self {
+        let render_state = cc.wgpu_render_state.as_ref().unwrap();
         let eframe::egui_wgpu::RenderState {
-            device, renderer, ..
-        } = cc.wgpu_render_state.as_ref().unwrap();
-
-        let main_texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Main Texture"),
-            size: wgpu::Extent3d {
-                width: 1,
-                height: 1,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::COPY_DST
-                | wgpu::TextureUsages::TEXTURE_BINDING
-                | wgpu::TextureUsages::STORAGE_BINDING,
-            view_formats: &[],
-        });
-
-        let output_texture_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Output Texture Bind Group Layout"),
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::StorageTexture {
-                        access: wgpu::StorageTextureAccess::WriteOnly,
-                        format: main_texture.format(),
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                    },
-                    count: None,
-                }],
-            });
-
-        let output_texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Output Texture Bind Group"),
-            layout: &output_texture_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: wgpu::BindingResource::TextureView(
-                    &main_texture.create_view(&wgpu::TextureViewDescriptor::default()),
-                ),
-            }],
-        });
+            adapter, device, ..
+        } = render_state;
+        tracing::info!(adapter = ?adapter.get_info(), "created renderer");
 
-        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Camera Buffer"),
-            size: <GpuCamera as ShaderSize>::SHADER_SIZE.get(),
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
-            mapped_at_creation: false,
-        });
-
-        let camera_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Camera Bind Group Layout"),
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: Some(<GpuCamera as ShaderSize>::SHADER_SIZE),
-                    },
-                    count: None,
-                }],
-            });
+        let diagnostics = diagnostics::Diagnostics::new(&cc.egui_ctx);
+        let jobs = jobs::Jobs::new(&cc.egui_ctx, diagnostics.clone());
 
-        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Camera Bind Group"),
-            layout: &camera_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
-        });
+        let gpu_info = gpu_info::GpuInfo::new(adapter, device);
+        let renderer_kind = if renderer_kind == RendererKind::Gpu && !gpu_info.supports_compute() {
+            tracing::warn!(
+                "the adapter doesn't support compute shaders, falling back to the CPU renderer"
+            );
+            RendererKind::Cpu
+        } else {
+            renderer_kind
+        };
+        let bug_report = bug_report::BugReport::new(&gpu_info, logs.clone());
+        bug_report.install_hooks(device, diagnostics.clone());
 
-        let compute_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Compute Pipeline Layout"),
-                bind_group_layouts: &[&output_texture_bind_group_layout, &camera_bind_group_layout],
-                push_constant_ranges: &[],
-            });
+        let main_texture = create_main_texture(device, 1, 1, renderer_kind == RendererKind::Gpu);
+        let renderer = match renderer_kind {
+            RendererKind::Gpu => {
+                Renderer::Gpu(gpu_renderer::GpuRenderer::new(render_state, &main_texture))
+            }
+            RendererKind::Cpu => Renderer::Cpu(cpu_renderer::CpuRenderer::new()),
+        };
 
-        let shader = device.create_shader_module(include_wgsl!("./shader.wgsl"));
-        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-            label: Some("Compute Pipeline"),
-            layout: Some(&compute_pipeline_layout),
-            module: &shader,
-            entry_point: "main",
+        let (autosave, recovered) = autosave::Autosave::new(diagnostics.clone());
+        let recovered_session = recovered.and_then(|recovered| {
+            serde_json::from_str(&recovered)
+                .map_err(|error| {
+                    diagnostics.report_error(&tr!("session-recovery-failed"), &error.into())
+                })
+                .ok()
         });
 
         Self {
-            egui_texture_id: renderer.write().register_native_texture(
+            egui_texture_id: render_state.renderer.write().register_native_texture(
                 device,
                 &main_texture.create_view(&wgpu::TextureViewDescriptor::default()),
                 wgpu::FilterMode::Nearest,
             ),
             main_texture,
-            output_texture_bind_group_layout,
-            output_texture_bind_group,
             camera: GpuCamera {
                 position: cgmath::vec2(0.0, 0.0),
                 height: 1.0,
                 player_position: cgmath::vec2(0.0, 0.0),
             },
-            camera_buffer,
-            camera_bind_group,
-            compute_pipeline,
-            camera_window: false,
+            tweaks: tweaks::Tweaks::reflect(include_str!("./shader.wgsl")).unwrap(),
+            renderer,
+            shader_reload: shader_reload::ShaderReload::new(&cc.egui_ctx),
+            capture: capture::Capture::new(
+                diagnostics.clone(),
+                jobs.clone(),
+                gpu_info.max_texture_size(),
+            ),
+            timeline: timeline::Timeline::new(),
+            compare: compare::Compare::new(diagnostics.clone()),
+            bookmarks: bookmarks::Bookmarks::load(cc.storage),
+            annotations: annotations::Annotations::load(cc.storage),
+            camera_transition: None,
+            time_control: time_control::TimeControl::new(),
+            viewport: egui::Rect::NOTHING,
+            camera_interaction_instant: None,
+            rendered_view: None,
+            dock: dock::Dock::load(cc.storage),
+            scopes: (renderer_kind == RendererKind::Gpu).then(|| scopes::Scopes::new(device)),
+            tutorial: tutorial::Tutorial::new(
+                cc.storage
+                    .and_then(|storage| eframe::get_value(storage, TUTORIAL_COMPLETED_KEY))
+                    .unwrap_or(false),
+            ),
+            command_palette: commands::CommandPalette::new(),
+            settings: settings::Settings::load(cc.storage),
+            cheat_sheet_window: false,
+            logs,
+            bug_report,
+            gpu_info,
+            autosave,
+            recovered_session,
+            last_frame_instant: Instant::now(),
+            last_frame_time: Duration::ZERO,
+            #[cfg(feature = "http-api")]
+            remote_commands: match remote::http::spawn(
+                remote::http::DEFAULT_ADDRESS,
+                cc.egui_ctx.clone(),
+            ) {
+                Ok(commands) => Some(commands),
+                Err(error) => {
+                    diagnostics.report_error(&tr!("http-api-failed-to-start"), &error);
+                    None
+                }
+            },
+            #[cfg(feature = "midi")]
+            midi: remote::midi::Midi::new(diagnostics.clone(), cc.storage),
+            #[cfg(feature = "osc")]
+            osc: remote::osc::Osc::new(diagnostics.clone(), cc.storage),
+            #[cfg(feature = "puffin")]
+            profiler_window: false,
+            diagnostics,
+            jobs,
+        }
+    }
+
+    fn execute(&mut self, command: Command) {
+        match command {
+            Command::CommandPalette => self.command_palette.open(),
+            Command::CheatSheet => self.cheat_sheet_window = !self.cheat_sheet_window,
+            Command::ToggleCameraWindow => self.dock.toggle(dock::Tab::Camera),
+            Command::ToggleCaptureWindow => self.dock.toggle(dock::Tab::Capture),
+            Command::ToggleTimelineWindow => self.dock.toggle(dock::Tab::Timeline),
+            Command::PlayPauseTimeline => self.timeline.toggle_playing(),
+            Command::StartStopCapture => {
+                if self.capture.is_active() {
+                    self.capture.stop();
+                } else {
+                    self.capture.start_with_settings();
+                }
+            }
+            Command::StartTutorial => self.tutorial.start(),
+            Command::ToggleLogWindow => self.dock.toggle(dock::Tab::Logs),
+            Command::AboutGpu => self.dock.toggle(dock::Tab::GpuInfo),
+            Command::StoreBookmark(slot) => self.bookmarks.store(slot, self.camera.pose()),
+            Command::RecallBookmark(slot) => {
+                if let Some(pose) = self.bookmarks.get(slot) {
+                    self.move_camera_to(pose);
+                }
+            }
+            #[cfg(feature = "renderdoc")]
+            Command::CaptureRenderDocFrame => self.capture_renderdoc_frame(),
+            Command::RenderStill => self.render_still(),
+            Command::ExportDiagnostics => match self.bug_report.write("exported by the user") {
+                Ok(path) => self.diagnostics.info(tr!(
+                    "diagnostics-exported",
+                    path = path.display().to_string()
+                )),
+                Err(error) => self
+                    .diagnostics
+                    .report_error(&tr!("diagnostics-export-failed"), &error),
+            },
+        }
+    }
+
+    /// Applies a value sent by an external controller, clamped so a stray message can't make the view degenerate
+    #[cfg(any(feature = "midi", feature = "osc"))]
+    fn set_remote_parameter(&mut self, parameter: Parameter, value: f32) {
+        let range = parameter.range();
+        *self.camera.parameter_mut(parameter) = value.clamp(*range.start(), *range.end());
+    }
+
+    /// Moves the camera to `pose`, animated if a transition duration is set
+    fn move_camera_to(&mut self, pose: timeline::CameraPose) {
+        let duration = self.settings.camera_transition_seconds;
+        if duration > 0.0 {
+            self.camera_transition = Some(transition::CameraTransition::new(
+                self.camera.pose(),
+                pose,
+                duration,
+            ));
+        } else {
+            self.camera.set_pose(pose);
+        }
+    }
+
+    /// Half the width and height of the area the camera sees, in world units
+    fn view_half_size(&self) -> cgmath::Vector2<f32> {
+        let aspect = self.main_texture.width() as f32 / self.main_texture.height() as f32;
+        cgmath::vec2(aspect * self.camera.height, self.camera.height) * 0.5
+    }
+
+    /// Converts a point in the viewport to world coordinates, must match the shader
+    fn screen_to_world(&self, position: egui::Pos2) -> cgmath::Vector2<f32> {
+        let uv = (position - self.viewport.min) / self.viewport.size();
+        let half_size = self.view_half_size();
+        // the image is drawn flipped, so up on screen is up in the world
+        cgmath::vec2(
+            (uv.x * 2.0 - 1.0) * half_size.x,
+            (1.0 - uv.y * 2.0) * half_size.y,
+        ) + self.camera.position
+    }
+
+    /// Converts a point in the world to a position in the viewport, the inverse of [`Self::screen_to_world`]
+    fn world_to_screen(&self, position: cgmath::Vector2<f32>) -> egui::Pos2 {
+        let half_size = self.view_half_size();
+        let offset = position - self.camera.position;
+        let uv = egui::vec2(
+            (offset.x / half_size.x + 1.0) * 0.5,
+            (1.0 - offset.y / half_size.y) * 0.5,
+        );
+        self.viewport.min + uv * self.viewport.size()
+    }
+
+    fn camera_ui(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(tr!("camera-position"));
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut self.camera.position.x)
+                        .prefix("x:")
+                        .speed(0.01),
+                )
+                .changed();
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut self.camera.position.y)
+                        .prefix("y:")
+                        .speed(0.01),
+                )
+                .changed();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("camera-view-height"));
+            changed |= ui
+                .add(egui::DragValue::new(&mut self.camera.height).speed(0.1))
+                .changed();
+            self.camera.height = self.camera.height.max(0.01);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("camera-player-position"));
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut self.camera.player_position.x)
+                        .prefix("x:")
+                        .speed(0.01),
+                )
+                .changed();
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut self.camera.player_position.y)
+                        .prefix("y:")
+                        .speed(0.01),
+                )
+                .changed();
+        });
+
+        if changed {
+            self.camera_interaction_instant = Some(Instant::now());
+        }
+    }
+
+    /// Pans the camera by dragging the viewport and zooms it by scrolling over it
+    fn viewport_camera_input(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        let mut moved = false;
+        if response.dragged() {
+            let delta = response.drag_delta() / self.viewport.size();
+            let half_size = self.view_half_size();
+            // the image is drawn flipped, so dragging down moves the camera up
+            self.camera.position.x -= delta.x * 2.0 * half_size.x;
+            self.camera.position.y += delta.y * 2.0 * half_size.y;
+            moved = true;
+        }
+        if response.hovered() {
+            let scroll = ui.input(|input| input.scroll_delta.y);
+            if scroll != 0.0 {
+                self.camera.height = (self.camera.height * (-scroll * 0.002).exp()).max(0.01);
+                moved = true;
+            }
+        }
+        if moved {
+            // the user takes the camera back from whatever was animating it
+            self.camera_transition = None;
+            self.camera_interaction_instant = Some(Instant::now());
+        }
+    }
+
+    fn statistics_ui(&self, ui: &mut egui::Ui) {
+        let output_texture =
+            self.main_texture.width() as u64 * self.main_texture.height() as u64 * 4;
+        let uniform_buffers = match &self.renderer {
+            Renderer::Gpu(gpu_renderer) => gpu_renderer.uniform_buffers_size(),
+            Renderer::Cpu(_) => 0,
+        };
+        let scopes = self.scopes.as_ref().map_or(0, scopes::Scopes::gpu_memory);
+
+        egui::Grid::new("Statistics").show(ui, |ui| {
+            ui.label(tr!("statistics-keyframes"));
+            ui.label(self.timeline.keyframe_count().to_string());
+            ui.end_row();
+
+            ui.label(tr!("statistics-output-texture"));
+            ui.label(format!(
+                "{}x{}, {}",
+                self.main_texture.width(),
+                self.main_texture.height(),
+                format_bytes(output_texture)
+            ));
+            ui.end_row();
+
+            ui.label(tr!("statistics-uniform-buffers"));
+            ui.label(format_bytes(uniform_buffers));
+            ui.end_row();
+
+            if let Renderer::Gpu(gpu_renderer) = &self.renderer {
+                ui.label(tr!("statistics-shader-permutations"));
+                ui.label(gpu_renderer.compiled_permutations().to_string());
+                ui.end_row();
+            }
+
+            ui.label(tr!("statistics-scopes-buffers"));
+            ui.label(format_bytes(scopes));
+            ui.end_row();
+
+            ui.strong(tr!("statistics-estimated-vram"));
+            ui.strong(format_bytes(output_texture + uniform_buffers + scopes));
+            ui.end_row();
+        });
+    }
+
+    #[cfg(feature = "renderdoc")]
+    fn capture_renderdoc_frame(&mut self) {
+        if renderdoc_capture::trigger_capture() {
+            // the captured frame has to actually dispatch something
+            self.rendered_view = None;
+            self.diagnostics.info(tr!("renderdoc-capture-triggered"));
+        } else {
+            self.diagnostics.error(tr!("renderdoc-unavailable"));
+        }
+    }
+
+    /// Moves the timeline and camera transitions forward by the time since the last frame
+    fn advance_time(&mut self, ctx: &egui::Context) {
+        let dt = self
+            .capture
+            .time_step()
+            // nothing is redrawn while idle, so the first frame after it would otherwise jump by the whole idle time
+            .unwrap_or(self.last_frame_time.as_secs_f32().min(MAX_TIME_STEP));
+        // captures advance by their fixed time step, pausing them would write the same frame forever
+        let animation_dt = if self.capture.is_active() {
+            dt
+        } else {
+            self.time_control.scale(dt)
+        };
+        if let Some(sample) = self.timeline.update(animation_dt) {
+            if let Some(pose) = sample.camera {
+                self.camera.set_pose(pose);
+            }
+            for (parameter, value) in sample.parameters {
+                *self.camera.parameter_mut(parameter) = value;
+            }
+            self.camera.height = self.camera.height.max(0.01);
+        }
+        if self.timeline.is_playing() {
+            // the timeline owns the camera while it plays
+            self.camera_transition = None;
+            // nothing moves while the time control is paused, so there is no need to keep redrawing
+            if animation_dt != 0.0 {
+                ctx.request_repaint();
+            }
+        }
+        if let Some(transition) = &mut self.camera_transition {
+            // transitions are part of navigating, not animations, so they ignore the time control
+            self.camera.set_pose(transition.update(dt));
+            if transition.is_finished() {
+                self.camera_transition = None;
+            }
+            ctx.request_repaint();
+        }
+    }
+
+    /// Renders the scene into the output texture at the size the viewport had last frame, and feeds the frame to
+    /// whatever reads it back, this has to happen even while the viewport tab is hidden
+    fn render(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let eframe::egui_wgpu::RenderState {
+            device,
+            queue,
+            renderer,
+            ..
+        } = frame.wgpu_render_state().unwrap();
+
+        if let Some(source) = self.shader_reload.poll() {
+            let reloaded = tweaks::Tweaks::reflect(&source).and_then(|tweaks| {
+                if let Renderer::Gpu(gpu_renderer) = &mut self.renderer {
+                    gpu_renderer.set_source(device, source.clone())?;
+                }
+                Ok(tweaks)
+            });
+            match reloaded {
+                Ok(tweaks) => {
+                    self.tweaks.reload(tweaks);
+                    self.rendered_view = None;
+                    self.diagnostics.info(tr!("shader-reloaded"));
+                }
+                Err(error) => {
+                    self.diagnostics
+                        .report_error(&tr!("shader-reload-failed"), &error);
+                    self.shader_reload.set_error(&error, source);
+                }
+            }
+        }
+
+        // render at a lower resolution while the user moves the camera, so dragging stays responsive on slow
+        // adapters, animations and remote control always render at the full resolution
+        let idle = Duration::from_secs_f32(self.settings.preview_idle_seconds);
+        let preview_scale = match self
+            .camera_interaction_instant
+            .map(|instant| instant.elapsed())
+        {
+            Some(since_interaction) if since_interaction < idle => {
+                ctx.request_repaint_after(idle - since_interaction);
+                self.settings.preview_resolution_scale
+            }
+            _ => 1.0,
+        };
+
+        // Resize output texture if needed, captures with a fixed resolution override the viewport size
+        let (width, height) = match self.capture.resolution() {
+            Some((width, height)) => (width as i64, height as i64),
+            // captured frames always use the full resolution
+            None if self.capture.is_active() => {
+                (self.viewport.width() as i64, self.viewport.height() as i64)
+            }
+            None => (
+                (self.viewport.width() * preview_scale) as i64,
+                (self.viewport.height() * preview_scale) as i64,
+            ),
+        };
+        let max_size = self.gpu_info.max_texture_size() as i64;
+        let (width, height) = (width.min(max_size), height.min(max_size));
+        if (self.main_texture.width() as i64 != width
+            || self.main_texture.height() as i64 != height)
+            && width > 0
+            && height > 0
+        {
+            tracing::debug!(width, height, "resizing output texture");
+            self.rendered_view = None;
+            self.main_texture = create_main_texture(
+                device,
+                width as _,
+                height as _,
+                matches!(self.renderer, Renderer::Gpu(_)),
+            );
+            renderer.write().update_egui_texture_from_wgpu_texture(
+                device,
+                &self
+                    .main_texture
+                    .create_view(&wgpu::TextureViewDescriptor::default()),
+                wgpu::FilterMode::Nearest,
+                self.egui_texture_id,
+            );
+            if let Renderer::Gpu(gpu_renderer) = &mut self.renderer {
+                gpu_renderer.set_output_texture(device, &self.main_texture);
+            }
+        }
+
+        let _span = tracing::trace_span!("render").entered();
+        profile_scope!("render");
+
+        let render_settings = self.render_settings();
+        let view = (self.camera, render_settings, self.tweaks.clone());
+        let refining = match &self.renderer {
+            Renderer::Cpu(cpu_renderer) => cpu_renderer.is_refining(),
+            Renderer::Gpu(_) => false,
+        };
+        // captures need a new frame every update, even if it looks the same as the last one
+        let new_frame =
+            self.rendered_view.as_ref() != Some(&view) || refining || self.capture.is_active();
+        if new_frame {
+            self.compare.invalidate();
+            if let Some(scopes) = &mut self.scopes {
+                scopes.invalidate();
+            }
+            self.rendered_view = Some(view);
+            match &mut self.renderer {
+                Renderer::Cpu(cpu_renderer) => {
+                    let size = self.main_texture.size();
+                    let pixels = cpu_renderer.render(
+                        size.width,
+                        size.height,
+                        &self.camera,
+                        &render_settings,
+                        &self.tweaks,
+                        // captured frames have to be complete
+                        !self.capture.is_active(),
+                    );
+                    if cpu_renderer.is_refining() {
+                        ctx.request_repaint();
+                    }
+                    queue.write_texture(
+                        self.main_texture.as_image_copy(),
+                        pixels,
+                        wgpu::ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: Some(size.width * 4),
+                            rows_per_image: None,
+                        },
+                        size,
+                    );
+                }
+                Renderer::Gpu(gpu_renderer) => gpu_renderer.render(
+                    device,
+                    queue,
+                    &self.main_texture,
+                    &self.camera,
+                    &render_settings,
+                    &self.tweaks,
+                ),
+            }
+        }
+
+        if self.capture.is_active() {
+            profile_scope!("capture");
+            match frame_sink::Frame::read_texture(device, queue, &self.main_texture) {
+                Ok(frame) => self.capture.write_frame(&frame),
+                Err(error) => {
+                    self.diagnostics
+                        .report_error(&tr!("frame-readback-failed"), &error);
+                    self.capture.stop();
+                }
+            }
+            // keep rendering frames while capturing even if nothing changes
+            ctx.request_repaint();
+        }
+
+        if self.compare.wants_frame() {
+            profile_scope!("compare");
+            match frame_sink::Frame::read_texture(device, queue, &self.main_texture) {
+                Ok(frame) => self.compare.update(ctx, frame),
+                Err(error) => self
+                    .diagnostics
+                    .report_error(&tr!("frame-readback-failed"), &error),
+            }
+        }
+
+        if self.dock.is_open(dock::Tab::Scopes) {
+            profile_scope!("scopes");
+            let Some(scopes) = &mut self.scopes else {
+                // left open from a run with the GPU renderer
+                self.dock.close(dock::Tab::Scopes);
+                return;
+            };
+            if let Err(error) = scopes.update(ctx, device, queue, &self.main_texture) {
+                self.diagnostics.report_error(&tr!("scopes-failed"), &error);
+                self.dock.close(dock::Tab::Scopes);
+            }
+        }
+    }
+
+    fn render_settings(&self) -> GpuRenderSettings {
+        GpuRenderSettings {
+            debug_palette: self.settings.debug_palette.gpu_index(),
+            background_pattern: self.settings.background_pattern.gpu_index(),
+        }
+    }
+
+    /// Renders the current view at twice the output size on the CPU in the background and saves it as a png
+    fn render_still(&self) {
+        let (width, height) = (
+            self.main_texture.width() * 2,
+            self.main_texture.height() * 2,
+        );
+        let camera = self.camera;
+        let settings = self.render_settings();
+        let tweaks = self.tweaks.clone();
+        let saved = tr!("render-still-saved", path = STILL_PATH);
+        let diagnostics = self.diagnostics.clone();
+        self.jobs
+            .spawn(tr!("command-render-still"), true, move |progress| {
+                let Some(frame) = cpu_renderer::render_still(
+                    width, height, &camera, &settings, &tweaks, progress,
+                ) else {
+                    return Ok(());
+                };
+                image::save_buffer(
+                    STILL_PATH,
+                    &frame.pixels,
+                    frame.width,
+                    frame.height,
+                    image::ColorType::Rgba8,
+                )?;
+                diagnostics.info(saved);
+                Ok(())
+            });
+    }
+
+    /// A summary of the current state for diagnostics bundles
+    fn scene_stats(&self) -> String {
+        let GpuCamera {
+            position,
+            height,
+            player_position,
+        } = self.camera;
+        format!(
+            "camera position: {position:?}\n\
+             camera height: {height}\n\
+             player position: {player_position:?}\n\
+             output size: {}x{}\n\
+             timeline duration: {}s\n\
+             capturing: {}\n\
+             frame time: {:?}",
+            self.main_texture.width(),
+            self.main_texture.height(),
+            self.timeline.duration(),
+            self.capture.is_active(),
+            self.last_frame_time,
+        )
+    }
+
+    #[cfg(feature = "http-api")]
+    fn handle_remote_commands(&mut self) {
+        use remote::http::{CameraState, CaptureState, FrameStats, RemoteCommand};
+
+        let Some(commands) = &self.remote_commands else {
+            return;
+        };
+        for command in commands.try_iter() {
+            match command {
+                RemoteCommand::SetCamera(update) => {
+                    if let Some([x, y]) = update.position {
+                        self.camera.position = cgmath::vec2(x, y);
+                    }
+                    if let Some(height) = update.height {
+                        self.camera.height = height.max(0.01);
+                    }
+                    if let Some([x, y]) = update.player_position {
+                        self.camera.player_position = cgmath::vec2(x, y);
+                    }
+                }
+                RemoteCommand::GetCamera(reply) => {
+                    _ = reply.send(CameraState {
+                        position: self.camera.position.into(),
+                        height: self.camera.height,
+                        player_position: self.camera.player_position.into(),
+                    });
+                }
+                RemoteCommand::GetFrameStats(reply) => {
+                    _ = reply.send(FrameStats {
+                        frame_time_ms: self.last_frame_time.as_secs_f32() * 1000.0,
+                        width: self.main_texture.width(),
+                        height: self.main_texture.height(),
+                    });
+                }
+                RemoteCommand::SetCapturing(capturing) => {
+                    if capturing && !self.capture.is_active() {
+                        self.capture.start_with_settings();
+                    } else if !capturing {
+                        self.capture.stop();
+                    }
+                }
+                RemoteCommand::GetCapture(reply) => {
+                    _ = reply.send(CaptureState {
+                        capturing: self.capture.is_active(),
+                    });
+                }
+            }
         }
     }
 }
 
+/// The texture the scene is rendered into, only the compute shader needs to write it as a storage texture
+fn create_main_texture(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+    storage: bool,
+) -> wgpu::Texture {
+    let mut usage = wgpu::TextureUsages::COPY_SRC
+        | wgpu::TextureUsages::COPY_DST
+        | wgpu::TextureUsages::TEXTURE_BINDING;
+    if storage {
+        usage |= wgpu::TextureUsages::STORAGE_BINDING;
+    }
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Main Texture"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage,
+        view_formats: &[],
+    })
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{bytes} B"),
+        1024..=1048575 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)),
+    }
+}
+
 impl eframe::App for App {
     fn update(&mut self, ctx: &eframe::egui::Context, frame: &mut eframe::Frame) {
+        #[cfg(feature = "puffin")]
+        {
+            // scopes are only recorded while someone is looking at them
+            puffin::set_scopes_on(self.profiler_window);
+            puffin::GlobalProfiler::lock().new_frame();
+        }
+        profile_scope!("update");
+
+        self.settings
+            .apply(ctx, frame.info().native_pixels_per_point);
+
+        // updates that come in before the frame limit allows only handle input and the ui, time doesn't advance
+        // and nothing is rendered until a repaint at the next allowed frame, captures should finish as fast as
+        // the renderer allows
+        let until_next_frame = self
+            .settings
+            .min_frame_time()
+            .filter(|_| !self.capture.is_active())
+            .map(|min_frame_time| {
+                (self.last_frame_instant + min_frame_time).saturating_duration_since(Instant::now())
+            })
+            .filter(|remaining| !remaining.is_zero());
+        let frame_due = until_next_frame.is_none();
+        if let Some(remaining) = until_next_frame {
+            ctx.request_repaint_after(remaining);
+        } else {
+            let now = Instant::now();
+            self.last_frame_time = now - self.last_frame_instant;
+            self.last_frame_instant = now;
+        }
+
+        self.jobs.update();
+
+        #[cfg(feature = "http-api")]
+        self.handle_remote_commands();
+
+        for command in Command::pressed(ctx) {
+            self.execute(command);
+        }
+
+        if self.timeline.take_render_started() {
+            self.capture.start_with_settings();
+        }
+        if self.timeline.take_render_finished() {
+            self.capture.stop();
+        }
+        if frame_due {
+            self.advance_time(ctx);
+        }
+
+        #[cfg(feature = "midi")]
+        for (parameter, value) in self.midi.poll() {
+            self.set_remote_parameter(parameter, value);
+        }
+        #[cfg(feature = "osc")]
+        for (parameter, value) in self.osc.poll() {
+            self.set_remote_parameter(parameter, value);
+        }
+
         egui::TopBottomPanel::top("Top Panel").show(ctx, |ui| {
+            profile_scope!("top panel");
             ui.horizontal(|ui| {
-                self.camera_window |= ui.button("Camera").clicked();
-            });
-        });
+                let camera_button = ui.button(tr!("camera"));
+                self.tutorial
+                    .register(tutorial::Target::CameraButton, camera_button.rect);
+                if camera_button.clicked() {
+                    self.dock.open(dock::Tab::Camera);
+                }
 
-        egui::Window::new("Camera")
-            .open(&mut self.camera_window)
-            .show(ctx, |ui| {
-                ui.horizontal(|ui| {
-                    ui.label("Position: ");
-                    ui.add(
-                        egui::DragValue::new(&mut self.camera.position.x)
-                            .prefix("x:")
-                            .speed(0.01),
-                    );
-                    ui.add(
-                        egui::DragValue::new(&mut self.camera.position.y)
-                            .prefix("y:")
-                            .speed(0.01),
-                    );
-                });
+                let capture_button = ui.button(tr!("capture"));
+                self.tutorial
+                    .register(tutorial::Target::CaptureButton, capture_button.rect);
+                if capture_button.clicked() {
+                    self.dock.open(dock::Tab::Capture);
+                }
 
-                ui.horizontal(|ui| {
-                    ui.label("View Height: ");
-                    ui.add(egui::DragValue::new(&mut self.camera.height).speed(0.1));
-                    self.camera.height = self.camera.height.max(0.01);
-                });
+                let timeline_button = ui.button(tr!("timeline"));
+                self.tutorial
+                    .register(tutorial::Target::TimelineButton, timeline_button.rect);
+                if timeline_button.clicked() {
+                    self.dock.open(dock::Tab::Timeline);
+                }
 
-                ui.horizontal(|ui| {
-                    ui.label("Player Position: ");
-                    ui.add(
-                        egui::DragValue::new(&mut self.camera.player_position.x)
-                            .prefix("x:")
-                            .speed(0.01),
-                    );
-                    ui.add(
-                        egui::DragValue::new(&mut self.camera.player_position.y)
-                            .prefix("y:")
-                            .speed(0.01),
-                    );
+                for tab in [
+                    dock::Tab::Bookmarks,
+                    dock::Tab::Annotations,
+                    dock::Tab::Compare,
+                    dock::Tab::Scopes,
+                    dock::Tab::Statistics,
+                    dock::Tab::Tweaks,
+                    dock::Tab::Tasks,
+                ] {
+                    let enabled = tab != dock::Tab::Scopes || self.scopes.is_some();
+                    if ui
+                        .add_enabled(enabled, egui::Button::new(tab.title()))
+                        .on_disabled_hover_text(tr!("scopes-gpu-only"))
+                        .clicked()
+                    {
+                        self.dock.open(tab);
+                    }
+                }
+
+                if ui.button(tr!("settings")).clicked() {
+                    self.dock.open(dock::Tab::Settings);
+                }
+
+                let unread_errors = self.diagnostics.unread_errors();
+                let diagnostics_button = if unread_errors > 0 {
+                    ui.button(
+                        egui::RichText::new(tr!("diagnostics-unread", count = unread_errors))
+                            .color(ui.visuals().error_fg_color),
+                    )
+                } else {
+                    ui.button(tr!("diagnostics"))
+                };
+                if diagnostics_button.clicked() {
+                    self.dock.open(dock::Tab::Messages);
+                }
+                #[cfg(feature = "midi")]
+                if ui.button(tr!("midi")).clicked() {
+                    self.dock.open(dock::Tab::Midi);
+                }
+                #[cfg(feature = "osc")]
+                if ui.button(tr!("osc")).clicked() {
+                    self.dock.open(dock::Tab::Osc);
+                }
+                #[cfg(feature = "puffin")]
+                {
+                    self.profiler_window |= ui.button(tr!("profiler")).clicked();
+                }
+                #[cfg(feature = "renderdoc")]
+                if ui
+                    .add_enabled(
+                        renderdoc_capture::is_available(),
+                        egui::Button::new(tr!("renderdoc-capture")),
+                    )
+                    .on_hover_text(tr!("renderdoc-capture-hint"))
+                    .on_disabled_hover_text(tr!("renderdoc-unavailable"))
+                    .clicked()
+                {
+                    self.capture_renderdoc_frame();
+                }
+                let help_menu = ui.menu_button(tr!("help"), |ui| {
+                    for command in [
+                        Command::CommandPalette,
+                        Command::CheatSheet,
+                        Command::StartTutorial,
+                        Command::ToggleLogWindow,
+                        Command::ExportDiagnostics,
+                        Command::AboutGpu,
+                    ] {
+                        if ui.button(command.name()).clicked() {
+                            self.execute(command);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                self.tutorial
+                    .register(tutorial::Target::HelpMenu, help_menu.response.rect);
+                ui.separator();
+                ui.add_enabled_ui(!self.capture.is_active(), |ui| self.time_control.ui(ui));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(tr!(
+                        "frame-time",
+                        milliseconds =
+                            format!("{:.2}", self.last_frame_time.as_secs_f64() * 1000.0)
+                    ));
                 });
+            });
+        });
+
+        #[cfg(feature = "puffin")]
+        if self.profiler_window {
+            self.profiler_window = puffin_egui::profiler_window(ctx);
+        }
 
-                ui.allocate_space(ui.available_size());
+        egui::TopBottomPanel::bottom("Status Bar").show(ctx, |ui| {
+            profile_scope!("status bar");
+            ui.horizontal(|ui| {
+                let half_size = self.view_half_size();
+                let min = self.camera.position - half_size;
+                let max = self.camera.position + half_size;
+                ui.label(tr!(
+                    "status-camera-rect",
+                    min = format!("({:.2}, {:.2})", min.x, min.y),
+                    max = format!("({:.2}, {:.2})", max.x, max.y)
+                ));
+                let running_jobs = self.jobs.running();
+                if running_jobs > 0 {
+                    ui.separator();
+                    ui.spinner();
+                    if ui
+                        .link(tr!("status-jobs-running", count = running_jobs))
+                        .clicked()
+                    {
+                        self.dock.open(dock::Tab::Tasks);
+                    }
+                }
+                if let Some(cursor) = ctx
+                    .pointer_hover_pos()
+                    .filter(|&cursor| self.viewport.contains(cursor))
+                {
+                    let cursor = self.screen_to_world(cursor);
+                    ui.separator();
+                    ui.label(tr!(
+                        "status-cursor",
+                        position = format!("({:.2}, {:.2})", cursor.x, cursor.y)
+                    ));
+                }
             });
+        });
+
+        if frame_due {
+            self.render(ctx, frame);
+        }
 
         egui::CentralPanel::default()
-            .frame(egui::Frame::none().fill(egui::Color32::from_rgb(255, 0, 255)))
+            .frame(egui::Frame::none())
             .show(ctx, |ui| {
-                let eframe::egui_wgpu::RenderState {
-                    device,
-                    queue,
-                    renderer,
-                    ..
-                } = frame.wgpu_render_state().unwrap();
-
-                let (_, rect) = ui.allocate_space(ui.available_size());
-
-                // Resize output texture if needed
-                let (width, height) = (rect.width() as i64, rect.height() as i64);
-                if self.main_texture.width() as i64 != width
-                    && self.main_texture.height() as i64 != height
-                    && width > 0
-                    && height > 0
-                {
-                    self.main_texture = device.create_texture(&wgpu::TextureDescriptor {
-                        label: Some("Main Texture"),
-                        size: wgpu::Extent3d {
-                            width: width as _,
-                            height: height as _,
-                            depth_or_array_layers: 1,
-                        },
-                        mip_level_count: 1,
-                        sample_count: 1,
-                        dimension: wgpu::TextureDimension::D2,
-                        format: wgpu::TextureFormat::Rgba8Unorm,
-                        usage: wgpu::TextureUsages::COPY_DST
-                            | wgpu::TextureUsages::TEXTURE_BINDING
-                            | wgpu::TextureUsages::STORAGE_BINDING,
-                        view_formats: &[],
+                // the dock borrows the app to draw the tabs, so it's taken out while it's shown
+                let mut dock = std::mem::replace(&mut self.dock.state, DockState::new(vec![]));
+                DockArea::new(&mut dock)
+                    .style(egui_dock::Style::from_egui(ui.style()))
+                    .show_inside(ui, &mut Tabs { app: self });
+                self.dock.state = dock;
+            });
+
+        if self.shader_reload.has_error() {
+            egui::Window::new(tr!("shader-error"))
+                .id(egui::Id::new("Shader Error"))
+                .show(ctx, |ui| self.shader_reload.ui(ui));
+        }
+
+        egui::Window::new(tr!("command-cheat-sheet"))
+            .id(egui::Id::new("Keyboard Shortcuts"))
+            .open(&mut self.cheat_sheet_window)
+            .show(ctx, commands::cheat_sheet_ui);
+
+        if let Some(command) = self.command_palette.show(ctx) {
+            self.execute(command);
+            ctx.request_repaint();
+        }
+
+        if self.recovered_session.is_some() {
+            let mut restore = None;
+            egui::Window::new(tr!("session-recovery"))
+                .id(egui::Id::new("Session Recovery"))
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(tr!("session-recovery-message"));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("session-recovery-restore")).clicked() {
+                            restore = Some(true);
+                        }
+                        if ui.button(tr!("session-recovery-discard")).clicked() {
+                            restore = Some(false);
+                        }
                     });
-                    renderer.write().update_egui_texture_from_wgpu_texture(
-                        device,
-                        &self
-                            .main_texture
-                            .create_view(&wgpu::TextureViewDescriptor::default()),
-                        wgpu::FilterMode::Nearest,
-                        self.egui_texture_id,
-                    );
-                    self.output_texture_bind_group =
-                        device.create_bind_group(&wgpu::BindGroupDescriptor {
-                            label: Some("Output Texture Bind Group"),
-                            layout: &self.output_texture_bind_group_layout,
-                            entries: &[wgpu::BindGroupEntry {
-                                binding: 0,
-                                resource: wgpu::BindingResource::TextureView(
-                                    &self
-                                        .main_texture
-                                        .create_view(&wgpu::TextureViewDescriptor::default()),
-                                ),
-                            }],
-                        });
+                });
+            match restore {
+                Some(true) => {
+                    let Session {
+                        camera,
+                        timeline,
+                        annotations,
+                    } = self.recovered_session.take().unwrap();
+                    self.camera = camera;
+                    self.timeline = timeline;
+                    self.annotations = annotations;
                 }
+                Some(false) => self.recovered_session = None,
+                None => {}
+            }
+        } else {
+            // the crashed session's autosave is kept until the user decides what to do with it
+            self.autosave.update(
+                Duration::from_secs(self.settings.autosave_interval_seconds.into()),
+                || {
+                    serde_json::to_string(&Session {
+                        camera: self.camera,
+                        timeline: self.timeline.clone(),
+                        annotations: self.annotations.clone(),
+                    })
+                    .unwrap()
+                },
+            );
+        }
 
-                // Upload camera uniform
-                {
-                    let mut buffer =
-                        UniformBuffer::new([0; <GpuCamera as ShaderSize>::SHADER_SIZE.get() as _]);
-                    buffer.write(&self.camera).unwrap();
-                    let buffer = buffer.into_inner();
-                    queue.write_buffer(&self.camera_buffer, 0, &buffer);
-                }
+        self.bug_report.set_scene_stats(self.scene_stats());
+        self.diagnostics.show_toasts(ctx);
+        self.tutorial.show(ctx);
 
-                let mut command_encoder =
-                    device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                        label: Some("Compute Command Encoder"),
-                    });
-                {
-                    let mut compute_pass =
-                        command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                            label: Some("Compute Pass"),
-                        });
-
-                    let (workgroup_width, workgroup_height) = (16, 16);
-                    let (workgroups_x, workgroups_y) = (
-                        (self.main_texture.width() + workgroup_width - 1) / workgroup_width,
-                        (self.main_texture.height() + workgroup_height - 1) / workgroup_height,
-                    );
+        #[cfg(feature = "tracy")]
+        if let Some(client) = tracy_client::Client::running() {
+            client.frame_mark();
+        }
+    }
 
-                    compute_pass.set_pipeline(&self.compute_pipeline);
-                    compute_pass.set_bind_group(0, &self.output_texture_bind_group, &[]);
-                    compute_pass.set_bind_group(1, &self.camera_bind_group, &[]);
-                    compute_pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
-                }
-                queue.submit([command_encoder.finish()]);
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(
+            storage,
+            TUTORIAL_COMPLETED_KEY,
+            &self.tutorial.is_completed(),
+        );
+        self.settings.save(storage);
+        self.bookmarks.save(storage);
+        self.annotations.save(storage);
+        self.dock.save(storage);
+        #[cfg(feature = "midi")]
+        self.midi.save(storage);
+        #[cfg(feature = "osc")]
+        self.osc.save(storage);
+    }
+
+    fn on_exit(&mut self) {
+        // a capture that is still being finished would be left truncated
+        self.capture.stop();
+        // nobody is around to see the results of jobs that can be cancelled anymore
+        self.jobs.cancel_all();
+        self.jobs.wait();
+        // keep the crashed session around if the user hasn't dealt with it yet
+        if self.recovered_session.is_none() {
+            self.autosave.remove();
+        }
+    }
+}
 
+/// Draws the tabs in the dock
+struct Tabs<'a> {
+    app: &'a mut App,
+}
+
+impl egui_dock::TabViewer for Tabs<'_> {
+    type Tab = dock::Tab;
+
+    fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
+        tab.title().into()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
+        let app = &mut *self.app;
+        match tab {
+            dock::Tab::Viewport => {
+                let (rect, response) =
+                    ui.allocate_exact_size(ui.available_size(), egui::Sense::click_and_drag());
+                app.tutorial.register(tutorial::Target::Viewport, rect);
+                app.viewport = rect;
+                app.viewport_camera_input(ui, &response);
+                if let Some(position) = response
+                    .interact_pointer_pos()
+                    .filter(|_| response.clicked())
+                {
+                    app.annotations.handle_click(app.screen_to_world(position));
+                }
                 ui.painter().image(
-                    self.egui_texture_id,
+                    app.egui_texture_id,
                     rect,
                     egui::Rect::from_min_max(egui::pos2(0.0, 1.0), egui::pos2(1.0, 0.0)),
                     egui::Color32::WHITE,
                 );
-            });
+                app.compare.paint(ui, rect);
+                app.annotations.paint(&ui.painter_at(rect), |position| {
+                    app.world_to_screen(position)
+                });
+            }
+            dock::Tab::Scopes => {
+                if let Some(scopes) = &mut app.scopes {
+                    scopes.ui(ui);
+                }
+            }
+            dock::Tab::Compare => app.compare.ui(ui),
+            dock::Tab::Statistics => app.statistics_ui(ui),
+            dock::Tab::Messages => app.diagnostics.log_ui(ui),
+            dock::Tab::Logs => app.logs.ui(ui),
+            dock::Tab::Tasks => app.jobs.ui(ui),
+            dock::Tab::Tweaks => app.tweaks.ui(ui),
+            dock::Tab::Camera => app.camera_ui(ui),
+            dock::Tab::Capture => app.capture.ui(ui),
+            dock::Tab::Timeline => {
+                let camera = &app.camera;
+                app.timeline
+                    .ui(ui, camera.pose(), |parameter| camera.parameter(parameter));
+            }
+            dock::Tab::Bookmarks => {
+                if let Some(pose) = app.bookmarks.ui(ui, app.camera.pose()) {
+                    app.move_camera_to(pose);
+                }
+            }
+            dock::Tab::Annotations => app.annotations.ui(ui, app.camera.position),
+            dock::Tab::Settings => app.settings.ui(ui),
+            dock::Tab::GpuInfo => app.gpu_info.ui(ui),
+            #[cfg(feature = "midi")]
+            dock::Tab::Midi => app.midi.ui(ui),
+            #[cfg(feature = "osc")]
+            dock::Tab::Osc => app.osc.ui(ui),
+        }
+    }
+
+    fn closeable(&mut self, tab: &mut Self::Tab) -> bool {
+        *tab != dock::Tab::Viewport
+    }
+
+    fn scroll_bars(&self, tab: &Self::Tab) -> [bool; 2] {
+        let scroll = *tab != dock::Tab::Viewport;
+        [scroll, scroll]
     }
 }
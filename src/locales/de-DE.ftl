@@ -0,0 +1,223 @@
+help = Hilfe
+settings = Einstellungen
+settings-language = Sprache
+settings-ui-scale = UI-Skalierung
+theme = Design
+theme-dark = Dunkel
+theme-light = Hell
+theme-high-contrast = Hoher Kontrast
+theme-custom-accent = Eigene Akzentfarbe
+settings-debug-palette = Debug-Farbpalette
+settings-autosave-interval = Automatisch speichern alle
+settings-camera-transition = Kameraübergang
+settings-preview-resolution = Auflösung beim Bewegen
+settings-preview-idle = Volle Auflösung nach
+settings-max-fps = Bildratenbegrenzung
+settings-max-fps-hint = Höchstens so viele Bilder pro Sekunde, 0 ist unbegrenzt
+debug-palette-red-green = Rot/Grün
+debug-palette-orange-blue = Orange/Blau (farbenblindsicher)
+debug-palette-grayscale = Graustufen
+settings-background-pattern = Hintergrundmuster
+background-pattern-none = Keines
+background-pattern-checker = Schachbrett
+background-pattern-graph-paper = Millimeterpapier
+frame-time = Bildzeit: { $milliseconds }ms
+time-pause = Animationen pausieren
+time-resume = Animationen fortsetzen
+time-step = Ein Bild weiter
+time-scale = Zeitskalierung
+
+diagnostics = Meldungen
+diagnostics-unread = Meldungen ({ $count })
+diagnostics-clear = Leeren
+logs = Protokoll
+logs-filter = Filtern
+logs-clear = Leeren
+autosave-failed = Automatisches Speichern fehlgeschlagen
+session-recovery = Sitzung wiederherstellen
+session-recovery-message = Die letzte Sitzung wurde nicht ordnungsgemäß beendet. Soll sie wiederhergestellt werden?
+session-recovery-restore = Wiederherstellen
+session-recovery-discard = Verwerfen
+session-recovery-failed = Die letzte Sitzung konnte nicht wiederhergestellt werden
+diagnostics-exported = Diagnosedaten wurden nach { $path } geschrieben
+diagnostics-export-failed = Diagnosedaten konnten nicht geschrieben werden
+gpu-error = Die GPU hat einen Fehler gemeldet, Diagnosedaten wurden nach { $path } geschrieben
+gpu-info-name = Name
+gpu-info-backend = Backend
+gpu-info-device-type = Gerätetyp
+gpu-info-driver = Treiber
+gpu-info-compute = Compute-Shader
+gpu-info-supported = Unterstützt
+gpu-info-unsupported = Nicht unterstützt
+gpu-info-max-texture-size = Maximale Texturgröße
+gpu-info-features = Features
+gpu-info-limits = Limits
+compare = Vergleich
+compare-snapshot = Schnappschuss aufnehmen
+compare-clear = Leeren
+compare-load = PNG laden
+compare-load-failed = Das Referenzbild konnte nicht geladen werden
+compare-no-reference = Nimm einen Schnappschuss auf oder lade ein PNG zum Vergleichen
+compare-wipe = Wischen
+compare-difference = Differenz
+compare-max-difference = Größte Differenz: { $percent }%
+compare-size-mismatch = Die Referenz ist { $width }x{ $height } groß, passe die Ansichtsgröße daran an
+scopes = Scopes
+scopes-histogram = Histogramm
+scopes-waveform = Luminanz-Wellenform
+scopes-logarithmic = Logarithmisch
+scopes-failed = Die Scopes konnten nicht erstellt werden
+scopes-gpu-only = Die Scopes benötigen den GPU-Renderer
+bookmarks = Lesezeichen
+bookmarks-hint = Strg+1 bis 9 speichert die Kamera, 1 bis 9 springt zu ihr zurück
+bookmarks-empty = Leer
+bookmarks-save = Speichern
+bookmarks-go-to = Springen
+bookmarks-clear = Leeren
+status-camera-rect = Ansicht: { $min } bis { $max }
+status-cursor = Cursor: { $position }
+viewport = Ansicht
+statistics = Statistiken
+statistics-keyframes = Timeline-Schlüsselbilder
+statistics-output-texture = Ausgabetextur
+statistics-uniform-buffers = Uniform-Puffer
+statistics-shader-permutations = Kompilierte Shader-Permutationen
+statistics-scopes-buffers = Scopes-Puffer
+statistics-estimated-vram = Geschätzter VRAM
+
+annotations = Anmerkungen
+annotations-add = In der Bildmitte hinzufügen
+annotations-place = Per Klick platzieren
+annotations-place-hint = Der nächste Klick in die Ansicht fügt dort eine Anmerkung hinzu
+annotations-default-text = Beschriftung
+annotations-label = Beschriftung
+annotations-target = Pfeil zu
+annotations-remove = Entfernen
+annotations-empty = Noch keine Anmerkungen
+
+jobs = Aufgaben
+jobs-none = Es läuft nichts
+jobs-cancel = Abbrechen
+job-failed = { $name } ist fehlgeschlagen
+status-jobs-running = Aufgaben ({ $count })
+http-api-failed-to-start = Die HTTP-API konnte nicht gestartet werden
+frame-readback-failed = Das Bild konnte nicht zurückgelesen werden
+capture-failed-to-start = Die Aufnahme konnte nicht gestartet werden
+capture-failed = Die Aufnahme ist fehlgeschlagen
+capture-finished = Aufnahme beendet, { $count } Bilder nach { $path } geschrieben
+capture-finishing = Aufnahme wird abgeschlossen
+midi-refresh-failed = MIDI-Anschlüsse konnten nicht aufgelistet werden
+midi-connect-failed = Verbindung zum MIDI-Anschluss fehlgeschlagen
+osc-listen-failed = OSC-Empfang konnte nicht gestartet werden
+
+camera = Kamera
+camera-position = Position: 
+camera-view-height = Sichthöhe: 
+camera-player-position = Spielerposition: 
+
+capture = Aufnahme
+capture-format = Format
+capture-format-png-sequence = PNG-Sequenz
+capture-format-gif = GIF
+capture-format-video = Video (ffmpeg)
+capture-directory = Verzeichnis: 
+capture-file = Datei: 
+capture-frames-per-second = Bilder pro Sekunde: 
+capture-codec = Codec
+capture-resolution = Auflösung: 
+capture-bitrate = Bitrate: 
+capture-frames-written = Geschriebene Bilder: { $count }
+capture-start = Starten
+capture-stop = Stoppen
+
+timeline = Zeitleiste
+timeline-play = Abspielen
+timeline-pause = Pause
+timeline-loop = Wiederholen
+timeline-render = Rendern
+timeline-render-tooltip = Rendert die gesamte Animation mit den Einstellungen aus dem Aufnahmefenster
+timeline-time = Zeit: 
+timeline-camera-track = Kamera
+timeline-add-keyframe = Schlüsselbild hinzufügen
+timeline-add-track = Spur hinzufügen
+timeline-remove-track = Spur entfernen
+timeline-set-to-current = Auf aktuellen Wert setzen
+timeline-go-to = Springen
+timeline-delete = Löschen
+
+easing-linear = Linear
+easing-ease-in = Einblenden
+easing-ease-out = Ausblenden
+easing-ease-in-out = Ein- und Ausblenden
+easing-step = Stufe
+
+parameter-camera-x = Kamera X
+parameter-camera-y = Kamera Y
+parameter-camera-height = Kamerahöhe
+parameter-player-x = Spieler X
+parameter-player-y = Spieler Y
+
+command-palette = Befehlspalette
+command-palette-hint = Befehl eingeben
+command-cheat-sheet = Tastenkürzel
+command-toggle-camera-window = Kamerafenster umschalten
+command-toggle-capture-window = Aufnahmefenster umschalten
+command-toggle-timeline-window = Zeitleistenfenster umschalten
+command-play-pause-timeline = Zeitleiste abspielen/pausieren
+command-start-stop-capture = Aufnahme starten/stoppen
+command-start-tutorial = Einführung starten
+command-toggle-log-window = Protokollfenster umschalten
+command-export-diagnostics = Diagnosedaten exportieren
+command-about-gpu = Über die GPU
+command-render-still = Standbild rendern
+command-store-bookmark = Kamera in Lesezeichen { $slot } speichern
+command-recall-bookmark = Zu Lesezeichen { $slot } springen
+render-still-saved = Standbild nach { $path } gespeichert
+
+tutorial-welcome-title = Willkommen
+tutorial-welcome-text = Dies ist ein 2D-Raytracer, diese kurze Tour zeigt, wo sich alles befindet.
+tutorial-viewport-title = Ansicht
+tutorial-viewport-text = Die gerenderte Welt füllt den Rest des Fensters und wird neu gezeichnet, sobald sich etwas ändert.
+tutorial-camera-title = Kamera
+tutorial-camera-text = Das Kamerafenster verschiebt die Ansicht, zoomt über die Sichthöhe und bewegt den Spieler.
+tutorial-timeline-title = Zeitleiste
+tutorial-timeline-text = Die Zeitleiste animiert die Kamera und andere Parameter mit Schlüsselbildern.
+tutorial-capture-title = Aufnahme
+tutorial-capture-text = Die Aufnahme speichert gerenderte Bilder als Bilder, GIFs oder Video und wird beim Rendern einer Zeitleiste verwendet.
+tutorial-help-title = Hilfe
+tutorial-help-text = Diese Tour kann jederzeit über das Hilfemenü erneut gestartet werden.
+tutorial-back = Zurück
+tutorial-next = Weiter
+tutorial-skip = Überspringen
+tutorial-done = Fertig
+
+binding-unbound = Nicht zugewiesen
+binding-learn = Lernen
+binding-clear = Entfernen
+
+midi = MIDI
+midi-connected-to = Verbunden mit: { $port }
+midi-disconnect = Trennen
+midi-refresh-ports = Anschlüsse aktualisieren
+midi-connect = Verbinden: { $port }
+midi-control-change = Kanal { $channel } CC { $controller }
+
+osc = OSC
+profiler = Profiler
+renderdoc-capture = RenderDoc-Aufnahme
+renderdoc-capture-hint = Nimmt das nächste Bild auf (F10)
+renderdoc-capture-triggered = RenderDoc nimmt das nächste Bild auf
+renderdoc-unavailable = RenderDoc konnte nicht geladen werden
+
+shader-error = Shaderfehler
+shader-error-hint = Der Shader konnte nicht kompiliert werden, die letzte funktionierende Version wird weiter verwendet
+shader-reloaded = Shader neu geladen
+tweaks = Shader-Regler
+tweaks-empty = Der Shader hat kein Tweaks-Struct
+tweaks-reset = Auf den Standardwert aus dem Shader zurücksetzen
+shader-reload-failed = Der Shader konnte nicht neu geladen werden, die letzte funktionierende Version wird weiter verwendet
+osc-listening-on = Empfange auf: { $address }
+osc-address = Adresse: 
+osc-listen = Empfangen
+osc-stop = Stoppen
+osc-addresses = Adressen
@@ -0,0 +1,223 @@
+help = Help
+settings = Settings
+settings-language = Language
+settings-ui-scale = UI Scale
+theme = Theme
+theme-dark = Dark
+theme-light = Light
+theme-high-contrast = High Contrast
+theme-custom-accent = Custom Accent Color
+settings-debug-palette = Debug Palette
+settings-autosave-interval = Autosave Every
+settings-camera-transition = Camera Transition
+settings-preview-resolution = Resolution While Moving
+settings-preview-idle = Full Resolution After
+settings-max-fps = Frame Rate Limit
+settings-max-fps-hint = At most this many frames per second, 0 is unlimited
+debug-palette-red-green = Red/Green
+debug-palette-orange-blue = Orange/Blue (Color Blind Safe)
+debug-palette-grayscale = Grayscale
+settings-background-pattern = Background Pattern
+background-pattern-none = None
+background-pattern-checker = Checker
+background-pattern-graph-paper = Graph Paper
+frame-time = Frame Time: { $milliseconds }ms
+time-pause = Pause animations
+time-resume = Resume animations
+time-step = Advance one frame
+time-scale = Time scale
+
+diagnostics = Messages
+diagnostics-unread = Messages ({ $count })
+diagnostics-clear = Clear
+logs = Logs
+logs-filter = Filter
+logs-clear = Clear
+autosave-failed = Failed to autosave
+session-recovery = Recover Session
+session-recovery-message = The last session didn't exit cleanly. Do you want to restore it?
+session-recovery-restore = Restore
+session-recovery-discard = Discard
+session-recovery-failed = Failed to recover the last session
+diagnostics-exported = Diagnostics were written to { $path }
+diagnostics-export-failed = Failed to write diagnostics
+gpu-error = The GPU reported an error, diagnostics were written to { $path }
+gpu-info-name = Name
+gpu-info-backend = Backend
+gpu-info-device-type = Device Type
+gpu-info-driver = Driver
+gpu-info-compute = Compute Shaders
+gpu-info-supported = Supported
+gpu-info-unsupported = Not Supported
+gpu-info-max-texture-size = Max Texture Size
+gpu-info-features = Features
+gpu-info-limits = Limits
+compare = Compare
+compare-snapshot = Take Snapshot
+compare-clear = Clear
+compare-load = Load PNG
+compare-load-failed = Failed to load the reference image
+compare-no-reference = Take a snapshot or load a png to compare against
+compare-wipe = Wipe
+compare-difference = Difference
+compare-max-difference = Largest difference: { $percent }%
+compare-size-mismatch = The reference is { $width }x{ $height }, resize the viewport to match it
+scopes = Scopes
+scopes-histogram = Histogram
+scopes-waveform = Luminance Waveform
+scopes-logarithmic = Logarithmic
+scopes-failed = Failed to build the scopes
+scopes-gpu-only = The scopes need the GPU renderer
+bookmarks = Bookmarks
+bookmarks-hint = Ctrl+1 to 9 saves the camera, 1 to 9 jumps back to it
+bookmarks-empty = Empty
+bookmarks-save = Save
+bookmarks-go-to = Go To
+bookmarks-clear = Clear
+status-camera-rect = View: { $min } to { $max }
+status-cursor = Cursor: { $position }
+viewport = Viewport
+statistics = Statistics
+statistics-keyframes = Timeline Keyframes
+statistics-output-texture = Output Texture
+statistics-uniform-buffers = Uniform Buffers
+statistics-shader-permutations = Compiled Shader Permutations
+statistics-scopes-buffers = Scopes Buffers
+statistics-estimated-vram = Estimated VRAM
+
+annotations = Annotations
+annotations-add = Add at View Center
+annotations-place = Place by Clicking
+annotations-place-hint = The next click in the viewport adds an annotation there
+annotations-default-text = Label
+annotations-label = Label
+annotations-target = Arrow to
+annotations-remove = Remove
+annotations-empty = No annotations yet
+
+jobs = Tasks
+jobs-none = Nothing is running
+jobs-cancel = Cancel
+job-failed = { $name } failed
+status-jobs-running = Tasks ({ $count })
+http-api-failed-to-start = Failed to start the HTTP API
+frame-readback-failed = Failed to read back the frame
+capture-failed-to-start = Failed to start the capture
+capture-failed = The capture failed
+capture-finished = Capture finished, { $count } frames written to { $path }
+capture-finishing = Finishing capture
+midi-refresh-failed = Failed to list MIDI ports
+midi-connect-failed = Failed to connect to the MIDI port
+osc-listen-failed = Failed to listen for OSC messages
+
+camera = Camera
+camera-position = Position: 
+camera-view-height = View Height: 
+camera-player-position = Player Position: 
+
+capture = Capture
+capture-format = Format
+capture-format-png-sequence = PNG Sequence
+capture-format-gif = GIF
+capture-format-video = Video (ffmpeg)
+capture-directory = Directory: 
+capture-file = File: 
+capture-frames-per-second = Frames Per Second: 
+capture-codec = Codec
+capture-resolution = Resolution: 
+capture-bitrate = Bitrate: 
+capture-frames-written = Frames Written: { $count }
+capture-start = Start
+capture-stop = Stop
+
+timeline = Timeline
+timeline-play = Play
+timeline-pause = Pause
+timeline-loop = Loop
+timeline-render = Render
+timeline-render-tooltip = Renders the whole animation with the settings in the capture window
+timeline-time = Time: 
+timeline-camera-track = Camera
+timeline-add-keyframe = Add Keyframe
+timeline-add-track = Add Track
+timeline-remove-track = Remove Track
+timeline-set-to-current = Set to Current
+timeline-go-to = Go To
+timeline-delete = Delete
+
+easing-linear = Linear
+easing-ease-in = Ease In
+easing-ease-out = Ease Out
+easing-ease-in-out = Ease In Out
+easing-step = Step
+
+parameter-camera-x = Camera X
+parameter-camera-y = Camera Y
+parameter-camera-height = Camera Height
+parameter-player-x = Player X
+parameter-player-y = Player Y
+
+command-palette = Command Palette
+command-palette-hint = Type a command
+command-cheat-sheet = Keyboard Shortcuts
+command-toggle-camera-window = Toggle Camera Window
+command-toggle-capture-window = Toggle Capture Window
+command-toggle-timeline-window = Toggle Timeline Window
+command-play-pause-timeline = Play/Pause Timeline
+command-start-stop-capture = Start/Stop Capture
+command-start-tutorial = Start Tutorial
+command-toggle-log-window = Toggle Log Window
+command-export-diagnostics = Export Diagnostics
+command-about-gpu = About GPU
+command-render-still = Render Still Image
+command-store-bookmark = Save Camera to Bookmark { $slot }
+command-recall-bookmark = Go to Bookmark { $slot }
+render-still-saved = Still image saved to { $path }
+
+tutorial-welcome-title = Welcome
+tutorial-welcome-text = This is a 2D ray tracer, this short tour shows where everything is.
+tutorial-viewport-title = Viewport
+tutorial-viewport-text = The rendered world fills the rest of the window, it is redrawn whenever something changes.
+tutorial-camera-title = Camera
+tutorial-camera-text = The camera window moves the view around the world, zooms with the view height, and moves the player.
+tutorial-timeline-title = Timeline
+tutorial-timeline-text = The timeline animates the camera and other parameters with keyframes.
+tutorial-capture-title = Capture
+tutorial-capture-text = Capture saves rendered frames as images, GIFs or video, and is used when rendering a timeline.
+tutorial-help-title = Help
+tutorial-help-text = This tour can be started again at any time from the help menu.
+tutorial-back = Back
+tutorial-next = Next
+tutorial-skip = Skip
+tutorial-done = Done
+
+binding-unbound = Unbound
+binding-learn = Learn
+binding-clear = Clear
+
+midi = MIDI
+midi-connected-to = Connected to: { $port }
+midi-disconnect = Disconnect
+midi-refresh-ports = Refresh Ports
+midi-connect = Connect: { $port }
+midi-control-change = Ch { $channel } CC { $controller }
+
+osc = OSC
+profiler = Profiler
+renderdoc-capture = RenderDoc Capture
+renderdoc-capture-hint = Captures the next frame (F10)
+renderdoc-capture-triggered = RenderDoc will capture the next frame
+renderdoc-unavailable = RenderDoc could not be loaded
+
+shader-error = Shader Error
+shader-error-hint = The shader failed to compile, the last working version is still being used
+shader-reloaded = Shader reloaded
+tweaks = Shader Tweaks
+tweaks-empty = The shader has no Tweaks struct
+tweaks-reset = Reset to the default from the shader
+shader-reload-failed = Failed to reload the shader, the last working version is still in use
+osc-listening-on = Listening on: { $address }
+osc-address = Address: 
+osc-listen = Listen
+osc-stop = Stop
+osc-addresses = Addresses
@@ -0,0 +1,119 @@
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+use unic_langid::LanguageIdentifier;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::German];
+
+    /// The name of the language in that language, so it can be found without understanding the current one
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+
+    fn identifier(self) -> LanguageIdentifier {
+        match self {
+            Self::English => "en-US",
+            Self::German => "de-DE",
+        }
+        .parse()
+        .unwrap()
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::English => include_str!("./locales/en-US.ftl"),
+            Self::German => include_str!("./locales/de-DE.ftl"),
+        }
+    }
+
+    fn bundle(self) -> FluentBundle<FluentResource> {
+        let resource = FluentResource::try_new(self.source().to_owned()).unwrap_or_else(
+            |(resource, errors)| {
+                tracing::warn!("errors in the {} locale: {errors:?}", self.native_name());
+                resource
+            },
+        );
+        let mut bundle = FluentBundle::new_concurrent(vec![self.identifier()]);
+        // egui can't render the unicode isolation marks that fluent puts around arguments
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(resource)
+            .expect("locale files should not define a message twice");
+        bundle
+    }
+}
+
+struct Localization {
+    language: Language,
+    bundle: FluentBundle<FluentResource>,
+    /// Used for any message the current language is missing
+    fallback: FluentBundle<FluentResource>,
+}
+
+/// Shared by every thread, messages are also translated on job threads and in wgpu callbacks
+static LOCALIZATION: OnceLock<RwLock<Localization>> = OnceLock::new();
+
+fn localization() -> &'static RwLock<Localization> {
+    LOCALIZATION.get_or_init(|| {
+        RwLock::new(Localization {
+            language: Language::English,
+            bundle: Language::English.bundle(),
+            fallback: Language::English.bundle(),
+        })
+    })
+}
+
+pub fn set_language(language: Language) {
+    // a panic while translating can't leave the bundles half updated, so a poisoned lock is still usable
+    let mut localization = localization()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if localization.language != language {
+        localization.language = language;
+        localization.bundle = language.bundle();
+    }
+}
+
+/// Looks up a message in the current language, use [`tr!`] instead of calling this directly
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let localization = localization()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    [&localization.bundle, &localization.fallback]
+        .into_iter()
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = vec![];
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .into_owned(),
+            )
+        })
+        // showing the id makes missing messages easy to spot
+        .unwrap_or_else(|| id.to_owned())
+}
+
+/// Translates a message id, optionally with named arguments: `tr!("frames-written", count = 5)`
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::localization::translate($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = ::fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::localization::translate($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
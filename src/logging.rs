@@ -0,0 +1,193 @@
+use crate::localization::tr;
+use eframe::egui;
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    sync::{Arc, Mutex, TryLockError},
+    time::Instant,
+};
+use tracing::{field::Field, level_filters::LevelFilter, Level, Subscriber};
+use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+/// Older records are dropped once the buffer gets this long
+const MAX_RECORDS: usize = 10_000;
+
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+#[derive(Clone)]
+struct Record {
+    time: Instant,
+    level: Level,
+    target: &'static str,
+    /// The names of the spans the event happened in, outermost first
+    spans: String,
+    message: String,
+}
+
+/// Keeps recent log records in memory so they can be shown in the app
+#[derive(Clone)]
+pub struct Logs {
+    start: Instant,
+    records: Arc<Mutex<VecDeque<Record>>>,
+    level: Level,
+    filter: String,
+}
+
+/// Sets up the global subscriber, logging to stderr and to the returned buffer
+pub fn init(level: Level) -> Logs {
+    let logs = Logs {
+        start: Instant::now(),
+        records: Arc::new(Mutex::new(VecDeque::new())),
+        level,
+        filter: String::new(),
+    };
+    let registry = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(BufferLayer {
+            records: logs.records.clone(),
+        })
+        .with(LevelFilter::from_level(level));
+    // spans show up as zones in tracy
+    #[cfg(feature = "tracy")]
+    let registry = registry.with(tracing_tracy::TracyLayer::new());
+    registry.init();
+    logs
+}
+
+impl Logs {
+    /// Formats the last `count` records as plain text, ignoring the filters in the log window
+    ///
+    /// Returns `None` if another thread holds the records, this is also called from the panic hook
+    /// where waiting would deadlock if the panicking thread is the one holding them
+    pub fn recent(&self, count: usize) -> Option<String> {
+        let records = match self.records.try_lock() {
+            Ok(records) => records,
+            Err(TryLockError::Poisoned(error)) => error.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+        let mut text = String::new();
+        for record in records.iter().skip(records.len().saturating_sub(count)) {
+            _ = writeln!(
+                text,
+                "{:.3}s {} {}{} {}",
+                (record.time - self.start).as_secs_f32(),
+                record.level,
+                record.target,
+                record.spans,
+                record.message,
+            );
+        }
+        Some(text)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("Log Level")
+                .selected_text(self.level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in LEVELS {
+                        ui.selectable_value(&mut self.level, level, level.as_str());
+                    }
+                });
+            ui.add(egui::TextEdit::singleline(&mut self.filter).hint_text(tr!("logs-filter")));
+            if ui.button(tr!("logs-clear")).clicked() {
+                self.records.lock().unwrap().clear();
+            }
+        });
+        ui.separator();
+
+        let filter = self.filter.to_lowercase();
+        // copied out so the lock isn't held while drawing, which can log through egui
+        let visible = self
+            .records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|record| record.level <= self.level)
+            .filter(|record| {
+                filter.is_empty()
+                    || record.message.to_lowercase().contains(&filter)
+                    || record.target.contains(&filter)
+                    || record.spans.contains(&filter)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::both()
+            .stick_to_bottom(true)
+            .auto_shrink([false; 2])
+            .show_rows(ui, row_height, visible.len(), |ui, rows| {
+                for record in &visible[rows] {
+                    let color = match record.level {
+                        Level::ERROR => ui.visuals().error_fg_color,
+                        Level::WARN => ui.visuals().warn_fg_color,
+                        _ => ui.visuals().text_color(),
+                    };
+                    ui.horizontal(|ui| {
+                        ui.weak(format!("{:.3}s", (record.time - self.start).as_secs_f32()));
+                        ui.colored_label(color, record.level.as_str());
+                        ui.weak(format!("{}{}", record.target, record.spans));
+                        ui.monospace(&record.message);
+                    });
+                }
+            });
+    }
+}
+
+struct BufferLayer {
+    records: Arc<Mutex<VecDeque<Record>>>,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for BufferLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor(String::new());
+        event.record(&mut visitor);
+
+        let mut spans = String::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                _ = write!(spans, ":{}", span.name());
+            }
+        }
+
+        let mut records = self.records.lock().unwrap();
+        if records.len() >= MAX_RECORDS {
+            records.pop_front();
+        }
+        records.push_back(Record {
+            time: Instant::now(),
+            level: *event.metadata().level(),
+            target: event.metadata().target(),
+            spans,
+            message: visitor.0,
+        });
+    }
+}
+
+/// Formats the message followed by the other fields as `name=value`
+struct MessageVisitor(String);
+
+impl tracing::field::Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            _ = write!(self.0, "{value:?}");
+        } else {
+            _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.push_str(value);
+        } else {
+            _ = write!(self.0, " {}={value}", field.name());
+        }
+    }
+}
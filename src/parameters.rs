@@ -0,0 +1,58 @@
+use crate::localization::tr;
+use serde::{Deserialize, Serialize};
+
+/// A scalar value that external controllers and the timeline can drive
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Parameter {
+    CameraX,
+    CameraY,
+    CameraHeight,
+    PlayerX,
+    PlayerY,
+}
+
+impl Parameter {
+    pub const ALL: [Self; 5] = [
+        Self::CameraX,
+        Self::CameraY,
+        Self::CameraHeight,
+        Self::PlayerX,
+        Self::PlayerY,
+    ];
+
+    pub fn name(self) -> String {
+        match self {
+            Self::CameraX => tr!("parameter-camera-x"),
+            Self::CameraY => tr!("parameter-camera-y"),
+            Self::CameraHeight => tr!("parameter-camera-height"),
+            Self::PlayerX => tr!("parameter-player-x"),
+            Self::PlayerY => tr!("parameter-player-y"),
+        }
+    }
+
+    #[cfg(feature = "osc")]
+    pub fn osc_address(self) -> &'static str {
+        match self {
+            Self::CameraX => "/camera/x",
+            Self::CameraY => "/camera/y",
+            Self::CameraHeight => "/camera/height",
+            Self::PlayerX => "/player/x",
+            Self::PlayerY => "/player/y",
+        }
+    }
+
+    /// The range that a normalized controller input in `0.0..=1.0` is mapped onto
+    #[cfg(any(feature = "midi", feature = "osc"))]
+    pub fn range(self) -> std::ops::RangeInclusive<f32> {
+        match self {
+            Self::CameraX | Self::CameraY | Self::PlayerX | Self::PlayerY => -10.0..=10.0,
+            Self::CameraHeight => 0.01..=20.0,
+        }
+    }
+
+    #[cfg(any(feature = "midi", feature = "osc"))]
+    pub fn denormalize(self, t: f32) -> f32 {
+        let range = self.range();
+        range.start() + (range.end() - range.start()) * t.clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,132 @@
+use eframe::wgpu;
+use std::collections::HashMap;
+
+/// The values baked into one variant of the shader, each replaces a `const NAME: u32 = ...;` line in the source
+pub type Permutation = [(&'static str, u32); 2];
+
+/// Compiles a pipeline for each combination of shader constants and keeps it around, so the shader doesn't
+/// branch on uniforms that are the same for every pixel
+///
+/// wgpu can't override constants at pipeline creation yet, so the source is specialized textually instead
+pub struct ShaderPermutations {
+    label: &'static str,
+    layout: wgpu::PipelineLayout,
+    source: String,
+    pipelines: HashMap<Permutation, wgpu::ComputePipeline>,
+}
+
+impl ShaderPermutations {
+    /// Compiles every permutation in `permutations` up front, so switching settings never stalls a frame on
+    /// shader compilation
+    pub fn new(
+        device: &wgpu::Device,
+        label: &'static str,
+        layout: wgpu::PipelineLayout,
+        source: String,
+        permutations: impl IntoIterator<Item = Permutation>,
+    ) -> Self {
+        let pipelines = permutations
+            .into_iter()
+            .map(|permutation| {
+                let pipeline = compile(device, label, &layout, &source, &permutation);
+                (permutation, pipeline)
+            })
+            .collect();
+        Self {
+            label,
+            layout,
+            source,
+            pipelines,
+        }
+    }
+
+    /// Replaces the source, recompiling every variant from it
+    ///
+    /// All the variants are compiled before any are replaced, so if wgpu rejects one of them the old source and
+    /// its variants are kept
+    pub fn set_source(&mut self, device: &wgpu::Device, source: String) -> anyhow::Result<()> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pipelines = self
+            .pipelines
+            .keys()
+            .map(|&permutation| {
+                let pipeline = compile(device, self.label, &self.layout, &source, &permutation);
+                (permutation, pipeline)
+            })
+            .collect();
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            anyhow::bail!("{error}");
+        }
+        self.source = source;
+        self.pipelines = pipelines;
+        Ok(())
+    }
+
+    /// How many variants are compiled
+    pub fn compiled(&self) -> usize {
+        self.pipelines.len()
+    }
+
+    /// The pipeline for `permutation`, which must be one of the permutations passed to [`Self::new`]
+    pub fn get(&self, permutation: Permutation) -> &wgpu::ComputePipeline {
+        &self.pipelines[&permutation]
+    }
+}
+
+fn compile(
+    device: &wgpu::Device,
+    label: &str,
+    layout: &wgpu::PipelineLayout,
+    source: &str,
+    permutation: &Permutation,
+) -> wgpu::ComputePipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(specialize(source, permutation).into()),
+    });
+    device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some(label),
+        layout: Some(layout),
+        module: &shader,
+        entry_point: "main",
+    })
+}
+
+/// Rewrites the value of each named `u32` constant, constants that aren't declared are left out
+fn specialize(source: &str, permutation: &Permutation) -> String {
+    source
+        .lines()
+        .map(|line| {
+            for &(name, value) in permutation {
+                if line.starts_with(&format!("const {name}: u32 =")) {
+                    return format!("const {name}: u32 = {value}u;");
+                }
+            }
+            line.to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::specialize;
+
+    #[test]
+    fn rewrites_declared_constants() {
+        let source = "const SAMPLES: u32 = 1u;\nconst BOUNCES: u32 = 4u; // per ray\nfn main() {}";
+        assert_eq!(
+            specialize(source, &[("SAMPLES", 8), ("BOUNCES", 2)]),
+            "const SAMPLES: u32 = 8u;\nconst BOUNCES: u32 = 2u;\nfn main() {}",
+        );
+    }
+
+    #[test]
+    fn leaves_everything_else_alone() {
+        let source = "const OTHER: u32 = 1u;\nconst SAMPLES: f32 = 1.0;\nlet x = SAMPLES;";
+        assert_eq!(
+            specialize(source, &[("SAMPLES", 8), ("BOUNCES", 2)]),
+            source
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! Wrappers around puffin and Tracy so call sites don't each need `cfg` attributes
+
+/// Profiles the rest of the enclosing scope, does nothing without the `puffin` or `tracy` features
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "puffin")]
+        puffin::profile_scope!($name);
+        #[cfg(feature = "tracy")]
+        let _tracy_span = tracy_client::span!($name);
+    };
+}
+pub(crate) use profile_scope;
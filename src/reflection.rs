@@ -0,0 +1,212 @@
+use anyhow::{bail, Context};
+use eframe::wgpu;
+
+/// Creates a bind group layout for every group the shader declares, so the layouts can't drift from the shader
+///
+/// Every binding is visible to all the stages the shader has entry points for
+pub fn bind_group_layouts(
+    device: &wgpu::Device,
+    label: &str,
+    source: &str,
+) -> anyhow::Result<Vec<wgpu::BindGroupLayout>> {
+    Ok(bind_group_entries(label, source)?
+        .iter()
+        .enumerate()
+        .map(|(group, entries)| {
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some(&format!("{label} Bind Group Layout {group}")),
+                entries,
+            })
+        })
+        .collect())
+}
+
+/// The entries of every bind group the shader declares, for checking whether a new version of it still fits
+/// the layouts that were created from the old one
+pub fn bind_group_entries(
+    label: &str,
+    source: &str,
+) -> anyhow::Result<Vec<Vec<wgpu::BindGroupLayoutEntry>>> {
+    let module = naga::front::wgsl::parse_str(source)
+        .map_err(|error| anyhow::anyhow!(error.emit_to_string(source)))?;
+
+    let visibility =
+        module
+            .entry_points
+            .iter()
+            .fold(wgpu::ShaderStages::NONE, |stages, entry_point| {
+                stages
+                    | match entry_point.stage {
+                        naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+                        naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+                        naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+                    }
+            });
+
+    let mut groups: Vec<Vec<wgpu::BindGroupLayoutEntry>> = vec![];
+    for (_, variable) in module.global_variables.iter() {
+        let Some(binding) = &variable.binding else {
+            continue;
+        };
+        let ty =
+            binding_type(variable.space, &module.types[variable.ty].inner).with_context(|| {
+                format!(
+                    "binding {} in group {} of {label}",
+                    binding.binding, binding.group
+                )
+            })?;
+        let group = binding.group as usize;
+        if groups.len() <= group {
+            groups.resize_with(group + 1, Vec::new);
+        }
+        groups[group].push(wgpu::BindGroupLayoutEntry {
+            binding: binding.binding,
+            visibility,
+            ty,
+            count: None,
+        });
+    }
+
+    for entries in &mut groups {
+        entries.sort_by_key(|entry| entry.binding);
+    }
+    Ok(groups)
+}
+
+fn binding_type(
+    space: naga::AddressSpace,
+    inner: &naga::TypeInner,
+) -> anyhow::Result<wgpu::BindingType> {
+    Ok(match (space, inner) {
+        (naga::AddressSpace::Uniform, _) => wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        (naga::AddressSpace::Storage { access }, _) => wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage {
+                read_only: !access.contains(naga::StorageAccess::STORE),
+            },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        (
+            naga::AddressSpace::Handle,
+            &naga::TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            },
+        ) => {
+            let view_dimension = view_dimension(dim, arrayed)?;
+            match class {
+                naga::ImageClass::Sampled { kind, multi } => wgpu::BindingType::Texture {
+                    sample_type: match kind {
+                        naga::ScalarKind::Sint => wgpu::TextureSampleType::Sint,
+                        naga::ScalarKind::Uint => wgpu::TextureSampleType::Uint,
+                        // samplers are reflected as filtering, so float textures have to be filterable to be
+                        // sampled with them
+                        naga::ScalarKind::Float => {
+                            wgpu::TextureSampleType::Float { filterable: true }
+                        }
+                        naga::ScalarKind::Bool => bail!("boolean textures aren't supported"),
+                    },
+                    view_dimension,
+                    multisampled: multi,
+                },
+                naga::ImageClass::Depth { multi } => wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Depth,
+                    view_dimension,
+                    multisampled: multi,
+                },
+                naga::ImageClass::Storage { format, access } => wgpu::BindingType::StorageTexture {
+                    access: match (
+                        access.contains(naga::StorageAccess::LOAD),
+                        access.contains(naga::StorageAccess::STORE),
+                    ) {
+                        (true, true) => wgpu::StorageTextureAccess::ReadWrite,
+                        (false, true) => wgpu::StorageTextureAccess::WriteOnly,
+                        _ => wgpu::StorageTextureAccess::ReadOnly,
+                    },
+                    format: storage_format(format)?,
+                    view_dimension,
+                },
+            }
+        }
+        (naga::AddressSpace::Handle, &naga::TypeInner::Sampler { comparison }) => {
+            wgpu::BindingType::Sampler(if comparison {
+                wgpu::SamplerBindingType::Comparison
+            } else {
+                wgpu::SamplerBindingType::Filtering
+            })
+        }
+        _ => bail!("unsupported binding"),
+    })
+}
+
+fn view_dimension(
+    dim: naga::ImageDimension,
+    arrayed: bool,
+) -> anyhow::Result<wgpu::TextureViewDimension> {
+    Ok(match (dim, arrayed) {
+        (naga::ImageDimension::D1, false) => wgpu::TextureViewDimension::D1,
+        (naga::ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+        (naga::ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+        (naga::ImageDimension::D3, false) => wgpu::TextureViewDimension::D3,
+        (naga::ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+        (naga::ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray,
+        _ => bail!("unsupported texture dimension"),
+    })
+}
+
+/// Only the formats the shaders actually use, more can be added as they're needed
+fn storage_format(format: naga::StorageFormat) -> anyhow::Result<wgpu::TextureFormat> {
+    Ok(match format {
+        naga::StorageFormat::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm,
+        naga::StorageFormat::Rgba8Snorm => wgpu::TextureFormat::Rgba8Snorm,
+        naga::StorageFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+        naga::StorageFormat::Rgba32Float => wgpu::TextureFormat::Rgba32Float,
+        naga::StorageFormat::R32Float => wgpu::TextureFormat::R32Float,
+        naga::StorageFormat::R32Uint => wgpu::TextureFormat::R32Uint,
+        _ => bail!("unsupported storage texture format {format:?}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bind_group_entries;
+    use eframe::wgpu;
+
+    #[test]
+    fn sampled_textures_can_be_filtered_by_their_samplers() {
+        let source = "
+            @group(0) @binding(0) var color: texture_2d<f32>;
+            @group(0) @binding(1) var color_sampler: sampler;
+            @group(1) @binding(0) var<uniform> scale: f32;
+
+            @fragment
+            fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+                return textureSample(color, color_sampler, uv) * scale;
+            }
+        ";
+        let groups = bind_group_entries("Test", source).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), 2);
+        assert_eq!(
+            groups[0][0].ty,
+            wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            }
+        );
+        assert_eq!(
+            groups[0][1].ty,
+            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)
+        );
+        assert!(groups[0]
+            .iter()
+            .all(|entry| entry.visibility == wgpu::ShaderStages::FRAGMENT));
+        assert_eq!(groups[1][0].binding, 0);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{localization::tr, parameters::Parameter};
+use eframe::egui;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, hash::Hash};
+
+/// Maps controller inputs to the parameters they drive
+pub struct Bindings<K> {
+    bindings: HashMap<K, Parameter>,
+    /// The next input received will be bound to this parameter
+    pub learning: Option<Parameter>,
+}
+
+impl<K: Clone + Eq + Hash + Serialize + DeserializeOwned> Bindings<K> {
+    /// Loads the bindings learned in earlier sessions
+    pub fn load(storage: Option<&dyn eframe::Storage>, key: &str) -> Self {
+        // stored as a list since not every input type can be a map key
+        let bindings: Vec<(K, Parameter)> = storage
+            .and_then(|storage| eframe::get_value(storage, key))
+            .unwrap_or_default();
+        Self {
+            bindings: bindings.into_iter().collect(),
+            learning: None,
+        }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage, key: &str) {
+        let bindings: Vec<(&K, &Parameter)> = self.bindings.iter().collect();
+        eframe::set_value(storage, key, &bindings);
+    }
+
+    /// Binds the input if a parameter is being learned, then returns the parameter the input is bound to
+    pub fn handle(&mut self, input: &K) -> Option<Parameter> {
+        if let Some(parameter) = self.learning.take() {
+            self.unbind(parameter);
+            self.bindings.insert(input.clone(), parameter);
+        }
+        self.bindings.get(input).copied()
+    }
+
+    pub fn bound_to(&self, parameter: Parameter) -> Option<&K> {
+        self.bindings
+            .iter()
+            .find_map(|(input, &bound)| (bound == parameter).then_some(input))
+    }
+
+    pub fn unbind(&mut self, parameter: Parameter) {
+        self.bindings.retain(|_, bound| *bound != parameter);
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, describe: impl Fn(&K) -> String) {
+        egui::Grid::new("Bindings").show(ui, |ui| {
+            for parameter in Parameter::ALL {
+                ui.label(parameter.name());
+                match self.bound_to(parameter) {
+                    Some(input) => ui.label(describe(input)),
+                    None => ui.weak(tr!("binding-unbound")),
+                };
+                let learning = self.learning == Some(parameter);
+                if ui
+                    .selectable_label(learning, tr!("binding-learn"))
+                    .clicked()
+                {
+                    self.learning = if learning { None } else { Some(parameter) };
+                }
+                if ui.button(tr!("binding-clear")).clicked() {
+                    self.unbind(parameter);
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
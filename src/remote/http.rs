@@ -0,0 +1,145 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Cursor, Read},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// A request from an external controller, applied by the app at the start of its next update
+pub enum RemoteCommand {
+    SetCamera(CameraUpdate),
+    GetCamera(Sender<CameraState>),
+    GetFrameStats(Sender<FrameStats>),
+    /// Starts a capture with the settings from the capture window, or stops the running one
+    SetCapturing(bool),
+    GetCapture(Sender<CaptureState>),
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub struct CameraState {
+    pub position: [f32; 2],
+    pub height: f32,
+    pub player_position: [f32; 2],
+}
+
+/// Every field is optional so a controller can change only the parts of the camera it cares about
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct CameraUpdate {
+    pub position: Option<[f32; 2]>,
+    pub height: Option<f32>,
+    pub player_position: Option<[f32; 2]>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub struct FrameStats {
+    pub frame_time_ms: f32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct CaptureState {
+    pub capturing: bool,
+}
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+/// How long a query waits for the app to answer before the request fails
+const REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Starts the HTTP server on a background thread, the returned receiver must be drained by the app every frame
+pub fn spawn(address: &str, ctx: egui::Context) -> anyhow::Result<Receiver<RemoteCommand>> {
+    let server = Server::http(address).map_err(|error| anyhow::anyhow!(error))?;
+    tracing::info!("HTTP API listening on {address}");
+    let (commands, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("HTTP API".into())
+        .spawn(move || {
+            for mut request in server.incoming_requests() {
+                let span = tracing::debug_span!("request", method = %request.method(), url = request.url());
+                let _guard = span.enter();
+                let response = handle_request(&mut request, &commands, &ctx);
+                tracing::debug!(status = response.status_code().0, "responded");
+                _ = request.respond(response);
+            }
+        })?;
+    Ok(receiver)
+}
+
+fn handle_request(
+    request: &mut Request,
+    commands: &Sender<RemoteCommand>,
+    ctx: &egui::Context,
+) -> Response<Cursor<Vec<u8>>> {
+    // the query string isn't used, but clients may add one to bust caches
+    let path = request
+        .url()
+        .split_once('?')
+        .map_or(request.url(), |(path, _)| path);
+    match (request.method(), path) {
+        (Method::Get, "/camera") => query(commands, ctx, RemoteCommand::GetCamera),
+        (Method::Put | Method::Post, "/camera") => {
+            let update = match read_json::<CameraUpdate>(request) {
+                Ok(update) => update,
+                Err(response) => return response,
+            };
+            send(commands, ctx, RemoteCommand::SetCamera(update));
+            json_response(200, &serde_json::json!({ "ok": true }))
+        }
+        (Method::Get, "/stats") => query(commands, ctx, RemoteCommand::GetFrameStats),
+        (Method::Get, "/capture") => query(commands, ctx, RemoteCommand::GetCapture),
+        (Method::Post, "/capture") => {
+            let state = match read_json::<CaptureState>(request) {
+                Ok(state) => state,
+                Err(response) => return response,
+            };
+            send(commands, ctx, RemoteCommand::SetCapturing(state.capturing));
+            json_response(200, &serde_json::json!({ "ok": true }))
+        }
+        _ => error_response(404, "not found"),
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(
+    request: &mut Request,
+) -> Result<T, Response<Cursor<Vec<u8>>>> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|error| error_response(400, &error.to_string()))?;
+    serde_json::from_str(&body).map_err(|error| error_response(400, &error.to_string()))
+}
+
+fn send(commands: &Sender<RemoteCommand>, ctx: &egui::Context, command: RemoteCommand) {
+    _ = commands.send(command);
+    // the app may be idle, so wake it up to handle the command
+    ctx.request_repaint();
+}
+
+fn query<T: Serialize>(
+    commands: &Sender<RemoteCommand>,
+    ctx: &egui::Context,
+    command: impl FnOnce(Sender<T>) -> RemoteCommand,
+) -> Response<Cursor<Vec<u8>>> {
+    let (reply, receiver) = mpsc::channel();
+    send(commands, ctx, command(reply));
+    match receiver.recv_timeout(REPLY_TIMEOUT) {
+        Ok(value) => json_response(200, &value),
+        Err(_) => error_response(503, "the app did not respond"),
+    }
+}
+
+fn json_response(status: u16, value: &impl Serialize) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(serde_json::to_string(value).unwrap())
+        .with_status_code(status)
+        .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap())
+}
+
+fn error_response(status: u16, message: &str) -> Response<Cursor<Vec<u8>>> {
+    json_response(status, &serde_json::json!({ "error": message }))
+}
@@ -0,0 +1,152 @@
+use super::Bindings;
+use crate::{diagnostics::Diagnostics, localization::tr, parameters::Parameter};
+use eframe::egui;
+use midir::{MidiInput, MidiInputConnection};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver};
+
+const CLIENT_NAME: &str = "2D Ray Tracing";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct ControlChange {
+    pub channel: u8,
+    pub controller: u8,
+}
+
+struct Connection {
+    port_name: String,
+    // kept alive so the callback keeps receiving messages
+    _connection: MidiInputConnection<()>,
+    events: Receiver<(ControlChange, u8)>,
+}
+
+pub struct Midi {
+    connection: Option<Connection>,
+    port_names: Vec<String>,
+    diagnostics: Diagnostics,
+    pub bindings: Bindings<ControlChange>,
+}
+
+impl Midi {
+    const BINDINGS_KEY: &'static str = "midi_bindings";
+
+    pub fn new(diagnostics: Diagnostics, storage: Option<&dyn eframe::Storage>) -> Self {
+        Self {
+            connection: None,
+            port_names: vec![],
+            diagnostics,
+            bindings: Bindings::load(storage, Self::BINDINGS_KEY),
+        }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        self.bindings.save(storage, Self::BINDINGS_KEY);
+    }
+
+    pub fn refresh_ports(&mut self) -> anyhow::Result<()> {
+        let input = MidiInput::new(CLIENT_NAME)?;
+        self.port_names = input
+            .ports()
+            .iter()
+            .filter_map(|port| input.port_name(port).ok())
+            .collect();
+        Ok(())
+    }
+
+    pub fn connect(&mut self, port_name: &str, ctx: egui::Context) -> anyhow::Result<()> {
+        self.connection = None;
+
+        let input = MidiInput::new(CLIENT_NAME)?;
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|port| input.port_name(port).is_ok_and(|name| name == port_name))
+            .ok_or_else(|| anyhow::anyhow!("MIDI port '{port_name}' no longer exists"))?;
+
+        let (sender, events) = mpsc::channel();
+        let connection = input
+            .connect(
+                &port,
+                CLIENT_NAME,
+                move |_timestamp, message, _| {
+                    // only control change messages are used, which are 0xBn where n is the channel
+                    if let &[status, controller, value] = message {
+                        if status & 0xF0 == 0xB0 {
+                            let control = ControlChange {
+                                channel: status & 0x0F,
+                                controller,
+                            };
+                            _ = sender.send((control, value));
+                            ctx.request_repaint();
+                        }
+                    }
+                },
+                (),
+            )
+            .map_err(|error| anyhow::anyhow!("{error}"))?;
+
+        tracing::info!("connected to MIDI port '{port_name}'");
+        self.connection = Some(Connection {
+            port_name: port_name.to_owned(),
+            _connection: connection,
+            events,
+        });
+        Ok(())
+    }
+
+    /// Returns the new value of every parameter that a received control change is bound to
+    pub fn poll(&mut self) -> Vec<(Parameter, f32)> {
+        let Some(connection) = &self.connection else {
+            return vec![];
+        };
+        connection
+            .events
+            .try_iter()
+            .filter_map(|(control, value)| {
+                let parameter = self.bindings.handle(&control)?;
+                Some((parameter, parameter.denormalize(value as f32 / 127.0)))
+            })
+            .collect()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(connection) = &self.connection {
+            ui.horizontal(|ui| {
+                ui.label(tr!(
+                    "midi-connected-to",
+                    port = connection.port_name.as_str()
+                ));
+                if ui.button(tr!("midi-disconnect")).clicked() {
+                    self.connection = None;
+                }
+            });
+        } else {
+            if ui.button(tr!("midi-refresh-ports")).clicked() {
+                if let Err(error) = self.refresh_ports() {
+                    self.diagnostics
+                        .report_error(&tr!("midi-refresh-failed"), &error);
+                }
+            }
+            for port_name in self.port_names.clone() {
+                if ui
+                    .button(tr!("midi-connect", port = port_name.as_str()))
+                    .clicked()
+                {
+                    if let Err(error) = self.connect(&port_name, ui.ctx().clone()) {
+                        self.diagnostics
+                            .report_error(&tr!("midi-connect-failed"), &error);
+                    }
+                }
+            }
+        }
+
+        ui.separator();
+        self.bindings.ui(ui, |control| {
+            tr!(
+                "midi-control-change",
+                channel = control.channel + 1,
+                controller = control.controller
+            )
+        });
+    }
+}
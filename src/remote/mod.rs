@@ -0,0 +1,11 @@
+#[cfg(any(feature = "midi", feature = "osc"))]
+mod bindings;
+#[cfg(feature = "http-api")]
+pub mod http;
+#[cfg(feature = "midi")]
+pub mod midi;
+#[cfg(feature = "osc")]
+pub mod osc;
+
+#[cfg(any(feature = "midi", feature = "osc"))]
+pub use bindings::Bindings;
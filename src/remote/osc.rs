@@ -0,0 +1,214 @@
+use super::Bindings;
+use crate::{diagnostics::Diagnostics, localization::tr, parameters::Parameter};
+use eframe::egui;
+use rosc::{OscPacket, OscType};
+use std::{
+    net::UdpSocket,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:9000";
+
+/// How often the listener thread checks whether it has been stopped
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+struct Listener {
+    address: String,
+    messages: Receiver<(String, f32)>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+pub struct Osc {
+    listener: Option<Listener>,
+    address: String,
+    diagnostics: Diagnostics,
+    pub bindings: Bindings<String>,
+}
+
+impl Osc {
+    const BINDINGS_KEY: &'static str = "osc_bindings";
+
+    pub fn new(diagnostics: Diagnostics, storage: Option<&dyn eframe::Storage>) -> Self {
+        Self {
+            listener: None,
+            address: DEFAULT_ADDRESS.to_owned(),
+            diagnostics,
+            bindings: Bindings::load(storage, Self::BINDINGS_KEY),
+        }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        self.bindings.save(storage, Self::BINDINGS_KEY);
+    }
+
+    pub fn listen(&mut self, ctx: egui::Context) -> anyhow::Result<()> {
+        self.listener = None;
+
+        let socket = UdpSocket::bind(&self.address)?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        let (sender, messages) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        thread::Builder::new().name("OSC Listener".into()).spawn({
+            let stop = stop.clone();
+            move || {
+                let mut buffer = [0; rosc::decoder::MTU];
+                while !stop.load(Ordering::Relaxed) {
+                    let Ok(size) = socket.recv(&mut buffer) else {
+                        continue;
+                    };
+                    match rosc::decoder::decode_udp(&buffer[..size]) {
+                        Ok((_, packet)) => {
+                            send_packet(packet, &sender);
+                            ctx.request_repaint();
+                        }
+                        Err(error) => tracing::debug!("ignoring invalid OSC packet: {error}"),
+                    }
+                }
+            }
+        })?;
+
+        tracing::info!("listening for OSC messages on {}", self.address);
+        self.listener = Some(Listener {
+            address: self.address.clone(),
+            messages,
+            stop,
+        });
+        Ok(())
+    }
+
+    /// Returns the new value of every parameter that a received message was routed to
+    ///
+    /// Messages sent to a parameter's own address set it directly,
+    /// any other address is looked up in the learned bindings and treated as a normalized value
+    pub fn poll(&mut self) -> Vec<(Parameter, f32)> {
+        let Some(listener) = &self.listener else {
+            return vec![];
+        };
+        let mut values = vec![];
+        for (address, value) in listener.messages.try_iter() {
+            let routed = Parameter::ALL
+                .into_iter()
+                .filter(|parameter| address_matches(&address, parameter.osc_address()))
+                .map(|parameter| (parameter, value))
+                .collect::<Vec<_>>();
+            if routed.is_empty() {
+                if let Some(parameter) = self.bindings.handle(&address) {
+                    values.push((parameter, parameter.denormalize(value)));
+                }
+            } else {
+                values.extend(routed);
+            }
+        }
+        values
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(listener) = &self.listener {
+            ui.horizontal(|ui| {
+                ui.label(tr!("osc-listening-on", address = listener.address.as_str()));
+                if ui.button(tr!("osc-stop")).clicked() {
+                    self.listener = None;
+                }
+            });
+        } else {
+            ui.horizontal(|ui| {
+                ui.label(tr!("osc-address"));
+                ui.text_edit_singleline(&mut self.address);
+                if ui.button(tr!("osc-listen")).clicked() {
+                    if let Err(error) = self.listen(ui.ctx().clone()) {
+                        self.diagnostics
+                            .report_error(&tr!("osc-listen-failed"), &error);
+                    }
+                }
+            });
+        }
+
+        ui.separator();
+        ui.collapsing(tr!("osc-addresses"), |ui| {
+            for parameter in Parameter::ALL {
+                ui.label(format!("{}: {}", parameter.name(), parameter.osc_address()));
+            }
+        });
+        self.bindings.ui(ui, |address| address.clone());
+    }
+}
+
+fn send_packet(packet: OscPacket, sender: &Sender<(String, f32)>) {
+    match packet {
+        OscPacket::Message(message) => {
+            let value = match message.args.first() {
+                Some(&OscType::Float(value)) => value,
+                Some(&OscType::Double(value)) => value as f32,
+                Some(&OscType::Int(value)) => value as f32,
+                Some(&OscType::Long(value)) => value as f32,
+                _ => return,
+            };
+            _ = sender.send((message.addr, value));
+        }
+        OscPacket::Bundle(bundle) => {
+            for packet in bundle.content {
+                send_packet(packet, sender);
+            }
+        }
+    }
+}
+
+/// Matches an OSC address pattern against an address, supporting the `*` and `?` wildcards within each part
+fn address_matches(pattern: &str, address: &str) -> bool {
+    fn part_matches(pattern: &[u8], part: &[u8]) -> bool {
+        match (pattern.split_first(), part.split_first()) {
+            (None, None) => true,
+            (Some((b'*', rest)), _) => {
+                part_matches(rest, part) || (!part.is_empty() && part_matches(pattern, &part[1..]))
+            }
+            (Some((b'?', rest)), Some((_, part_rest))) => part_matches(rest, part_rest),
+            (Some((p, rest)), Some((c, part_rest))) => p == c && part_matches(rest, part_rest),
+            _ => false,
+        }
+    }
+
+    let (mut pattern, mut address) = (pattern.split('/'), address.split('/'));
+    loop {
+        match (pattern.next(), address.next()) {
+            (None, None) => return true,
+            (Some(p), Some(a)) if part_matches(p.as_bytes(), a.as_bytes()) => {}
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::address_matches;
+
+    #[test]
+    fn exact_addresses() {
+        assert!(address_matches("/raytracer/shade", "/raytracer/shade"));
+        assert!(!address_matches("/raytracer/shade", "/raytracer/shades"));
+        assert!(!address_matches("/raytracer/shade", "/raytracer"));
+        assert!(!address_matches("/raytracer", "/raytracer/shade"));
+    }
+
+    #[test]
+    fn wildcards_stay_within_a_part() {
+        assert!(address_matches("/raytracer/*", "/raytracer/shade"));
+        assert!(address_matches("/raytracer/*", "/raytracer/"));
+        assert!(address_matches("/*/sha*e", "/raytracer/shade"));
+        assert!(!address_matches("/*", "/raytracer/shade"));
+        assert!(address_matches("/raytracer/sh?de", "/raytracer/shade"));
+        assert!(!address_matches("/raytracer/sh?de", "/raytracer/shde"));
+    }
+}
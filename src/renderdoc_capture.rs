@@ -0,0 +1,30 @@
+use renderdoc::{RenderDoc, V141};
+use std::cell::RefCell;
+
+thread_local! {
+    // renderdoc isn't thread safe, it's only used from the main thread
+    static RENDERDOC: RefCell<Option<RenderDoc<V141>>> = RefCell::new(None);
+}
+
+/// Loads the RenderDoc in-application API, must be called before the graphics device is created
+pub fn init() {
+    match RenderDoc::new() {
+        Ok(renderdoc) => RENDERDOC.with(|cell| *cell.borrow_mut() = Some(renderdoc)),
+        Err(error) => tracing::warn!(%error, "failed to load renderdoc"),
+    }
+}
+
+pub fn is_available() -> bool {
+    RENDERDOC.with(|cell| cell.borrow().is_some())
+}
+
+/// Captures the next presented frame, returning false if RenderDoc isn't loaded
+pub fn trigger_capture() -> bool {
+    RENDERDOC.with(|cell| match &mut *cell.borrow_mut() {
+        Some(renderdoc) => {
+            renderdoc.trigger_capture();
+            true
+        }
+        None => false,
+    })
+}
@@ -0,0 +1,237 @@
+use crate::{localization::tr, profiling::profile_scope, reflection};
+use eframe::{
+    egui,
+    wgpu::{self, include_wgsl},
+};
+use std::sync::mpsc;
+
+/// Must match `BINS` in `scopes.wgsl`
+const BINS: usize = 256;
+/// Must match `WAVEFORM_COLUMNS` in `scopes.wgsl`
+const WAVEFORM_COLUMNS: usize = 256;
+const HISTOGRAMS: usize = 4;
+const BUFFER_SIZE: u64 = ((BINS * HISTOGRAMS + BINS * WAVEFORM_COLUMNS) * 4) as u64;
+
+/// Histograms and a luminance waveform of the rendered image, built by a compute pass
+pub struct Scopes {
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bins_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    /// Red, green, blue and luminance
+    histograms: [[u32; BINS]; HISTOGRAMS],
+    waveform: Option<egui::TextureHandle>,
+    logarithmic: bool,
+    /// Set when a new frame was rendered, the scopes are only rebuilt then
+    outdated: bool,
+}
+
+impl Scopes {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let bind_group_layout =
+            reflection::bind_group_layouts(device, "Scopes", include_str!("./scopes.wgsl"))
+                .unwrap()
+                .remove(0);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Scopes Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let shader = device.create_shader_module(include_wgsl!("./scopes.wgsl"));
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Scopes Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "main",
+        });
+
+        let bins_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Scopes Bins Buffer"),
+            size: BUFFER_SIZE,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Scopes Readback Buffer"),
+            size: BUFFER_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            bins_buffer,
+            readback_buffer,
+            histograms: [[0; BINS]; HISTOGRAMS],
+            waveform: None,
+            logarithmic: false,
+            outdated: true,
+        }
+    }
+
+    /// The size of the buffers the scopes keep on the GPU, in bytes
+    pub fn gpu_memory(&self) -> u64 {
+        self.bins_buffer.size() + self.readback_buffer.size()
+    }
+
+    /// Called when the rendered image changed, so the next update rebuilds the scopes
+    pub fn invalidate(&mut self) {
+        self.outdated = true;
+    }
+
+    /// Rebuilds the scopes from the texture if it changed, blocking until the results are back on the CPU
+    #[tracing::instrument(skip_all)]
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+    ) -> anyhow::Result<()> {
+        if !std::mem::take(&mut self.outdated) {
+            return Ok(());
+        }
+        profile_scope!("scopes update");
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Scopes Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(
+                        &texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.bins_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Scopes Command Encoder"),
+        });
+        command_encoder.clear_buffer(&self.bins_buffer, 0, None);
+        {
+            let mut compute_pass =
+                command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("Scopes Pass"),
+                });
+            let (workgroup_width, workgroup_height) = (16, 16);
+            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            compute_pass.dispatch_workgroups(
+                (texture.width() + workgroup_width - 1) / workgroup_width,
+                (texture.height() + workgroup_height - 1) / workgroup_height,
+                1,
+            );
+        }
+        command_encoder.copy_buffer_to_buffer(
+            &self.bins_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            BUFFER_SIZE,
+        );
+        queue.submit([command_encoder.finish()]);
+
+        let slice = self.readback_buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver.recv()??;
+
+        let waveform = {
+            let data = slice.get_mapped_range();
+            let mut bins = data
+                .chunks_exact(4)
+                .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+            for histogram in &mut self.histograms {
+                histogram.fill_with(|| bins.next().unwrap());
+            }
+            let waveform = bins.collect::<Vec<_>>();
+            let peak = waveform.iter().copied().max().unwrap_or(0).max(1) as f32;
+            egui::ColorImage {
+                size: [WAVEFORM_COLUMNS, BINS],
+                pixels: waveform
+                    .into_iter()
+                    .map(|count| {
+                        // square root so sparse values are still visible next to dense ones
+                        let brightness = (count as f32 / peak).sqrt();
+                        egui::Color32::from_gray((brightness * 255.0) as u8)
+                    })
+                    .collect(),
+            }
+        };
+        self.readback_buffer.unmap();
+
+        match &mut self.waveform {
+            Some(texture) => texture.set(waveform, egui::TextureOptions::LINEAR),
+            None => {
+                self.waveform =
+                    Some(ctx.load_texture("Waveform", waveform, egui::TextureOptions::LINEAR));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.logarithmic, tr!("scopes-logarithmic"));
+
+        ui.label(tr!("scopes-histogram"));
+        let (_, rect) = ui.allocate_space(egui::vec2(ui.available_width().max(256.0), 128.0));
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
+        let scale = |count: u32| {
+            if self.logarithmic {
+                (count as f32).ln_1p()
+            } else {
+                count as f32
+            }
+        };
+        // the luminance histogram is left out of the peak since it overlaps the others
+        let peak = self.histograms[..3]
+            .iter()
+            .flatten()
+            .map(|&count| scale(count))
+            .fold(1.0, f32::max);
+        let colors = [
+            egui::Color32::RED,
+            egui::Color32::GREEN,
+            egui::Color32::BLUE,
+            egui::Color32::WHITE,
+        ];
+        for (histogram, color) in self.histograms.iter().zip(colors) {
+            let points = histogram
+                .iter()
+                .enumerate()
+                .map(|(bin, &count)| {
+                    rect.lerp_inside(egui::vec2(
+                        bin as f32 / (BINS - 1) as f32,
+                        1.0 - scale(count) / peak,
+                    ))
+                })
+                .collect();
+            painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, color)));
+        }
+
+        ui.label(tr!("scopes-waveform"));
+        let (_, rect) = ui.allocate_space(egui::vec2(ui.available_width().max(256.0), 128.0));
+        if let Some(waveform) = &self.waveform {
+            ui.painter().image(
+                waveform.id(),
+                rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+}
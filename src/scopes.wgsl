@@ -0,0 +1,36 @@
+@group(0)
+@binding(0)
+var image: texture_2d<f32>;
+
+// the red, green, blue and luminance histograms one after another, then the waveform
+@group(0)
+@binding(1)
+var<storage, read_write> bins: array<atomic<u32>>;
+
+// must match `BINS` and `WAVEFORM_COLUMNS` on the rust side
+const BINS: u32 = 256u;
+const WAVEFORM_COLUMNS: u32 = 256u;
+
+@compute
+@workgroup_size(16, 16)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+) {
+    let size = textureDimensions(image);
+    if global_id.x >= size.x || global_id.y >= size.y {
+        return;
+    }
+
+    let color = textureLoad(image, vec2<i32>(global_id.xy), 0).rgb;
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let bin = vec4<u32>(clamp(vec4<f32>(color, luminance), vec4<f32>(0.0), vec4<f32>(1.0)) * f32(BINS - 1u) + 0.5);
+
+    atomicAdd(&bins[bin.r], 1u);
+    atomicAdd(&bins[BINS + bin.g], 1u);
+    atomicAdd(&bins[BINS * 2u + bin.b], 1u);
+    atomicAdd(&bins[BINS * 3u + bin.a], 1u);
+
+    // the waveform has bright values at the top
+    let column = global_id.x * WAVEFORM_COLUMNS / size.x;
+    atomicAdd(&bins[BINS * 4u + (BINS - 1u - bin.a) * WAVEFORM_COLUMNS + column], 1u);
+}
@@ -0,0 +1,223 @@
+use crate::{
+    localization::{self, tr, Language},
+    theme::Theme,
+};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Colors used by debug visualizations
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DebugPalette {
+    #[default]
+    RedGreen,
+    /// Orange and sky blue from the Okabe-Ito palette, distinguishable with red-green color blindness
+    OrangeBlue,
+    Grayscale,
+}
+
+impl DebugPalette {
+    pub const ALL: [Self; 3] = [Self::RedGreen, Self::OrangeBlue, Self::Grayscale];
+
+    pub fn name(self) -> String {
+        match self {
+            Self::RedGreen => tr!("debug-palette-red-green"),
+            Self::OrangeBlue => tr!("debug-palette-orange-blue"),
+            Self::Grayscale => tr!("debug-palette-grayscale"),
+        }
+    }
+
+    /// Must match the `debug_palette` values in the shader
+    pub fn gpu_index(self) -> u32 {
+        match self {
+            Self::RedGreen => 0,
+            Self::OrangeBlue => 1,
+            Self::Grayscale => 2,
+        }
+    }
+}
+
+/// A pattern drawn where rays hit nothing, so camera movement is visible in empty space
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BackgroundPattern {
+    None,
+    #[default]
+    Checker,
+    GraphPaper,
+}
+
+impl BackgroundPattern {
+    pub const ALL: [Self; 3] = [Self::None, Self::Checker, Self::GraphPaper];
+
+    pub fn name(self) -> String {
+        match self {
+            Self::None => tr!("background-pattern-none"),
+            Self::Checker => tr!("background-pattern-checker"),
+            Self::GraphPaper => tr!("background-pattern-graph-paper"),
+        }
+    }
+
+    /// Must match the `background_pattern` values in the shader
+    pub fn gpu_index(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Checker => 1,
+            Self::GraphPaper => 2,
+        }
+    }
+}
+
+/// User preferences that are saved between runs
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub language: Language,
+    /// Multiplies the display's native scale
+    pub ui_scale: f32,
+    pub theme: Theme,
+    pub debug_palette: DebugPalette,
+    pub background_pattern: BackgroundPattern,
+    /// How often the editor state is autosaved for crash recovery
+    pub autosave_interval_seconds: u32,
+    /// How long the camera takes to move to a bookmark, 0 jumps instantly
+    pub camera_transition_seconds: f32,
+    /// The fraction of the viewport resolution rendered while the user moves the camera, 1 turns the preview off
+    pub preview_resolution_scale: f32,
+    /// How long the camera has to be still before rendering goes back to full resolution
+    pub preview_idle_seconds: f32,
+    /// Rendering doesn't wait for vsync, so without a limit small scenes render thousands of frames a second
+    ///
+    /// 0 is unlimited
+    pub max_fps: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            ui_scale: 1.0,
+            theme: Theme::default(),
+            debug_palette: DebugPalette::default(),
+            background_pattern: BackgroundPattern::default(),
+            autosave_interval_seconds: 30,
+            camera_transition_seconds: 0.5,
+            preview_resolution_scale: 0.5,
+            preview_idle_seconds: 0.25,
+            max_fps: 144,
+        }
+    }
+}
+
+impl Settings {
+    const KEY: &'static str = "settings";
+
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|storage| eframe::get_value(storage, Self::KEY))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::KEY, self);
+    }
+
+    /// Applies the settings that live outside of this struct, called at the start of every frame
+    pub fn apply(&self, ctx: &egui::Context, native_pixels_per_point: Option<f32>) {
+        localization::set_language(self.language);
+
+        let pixels_per_point = native_pixels_per_point.unwrap_or(1.0) * self.ui_scale;
+        if (ctx.pixels_per_point() - pixels_per_point).abs() > f32::EPSILON {
+            ctx.set_pixels_per_point(pixels_per_point);
+        }
+
+        let visuals = self.theme.visuals();
+        if ctx.style().visuals != visuals {
+            ctx.set_visuals(visuals);
+        }
+    }
+
+    /// The shortest time a frame may take, `None` if the frame rate isn't limited
+    pub fn min_frame_time(&self) -> Option<Duration> {
+        (self.max_fps > 0).then(|| Duration::from_secs_f64(1.0 / self.max_fps as f64))
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label(tr!("settings-language"))
+            .selected_text(self.language.native_name())
+            .show_ui(ui, |ui| {
+                for language in Language::ALL {
+                    ui.selectable_value(&mut self.language, language, language.native_name());
+                }
+            });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-ui-scale"));
+            ui.add(
+                egui::DragValue::new(&mut self.ui_scale)
+                    .clamp_range(0.5..=3.0)
+                    .speed(0.01),
+            );
+        });
+
+        self.theme.ui(ui);
+
+        egui::ComboBox::from_label(tr!("settings-debug-palette"))
+            .selected_text(self.debug_palette.name())
+            .show_ui(ui, |ui| {
+                for palette in DebugPalette::ALL {
+                    ui.selectable_value(&mut self.debug_palette, palette, palette.name());
+                }
+            });
+
+        egui::ComboBox::from_label(tr!("settings-background-pattern"))
+            .selected_text(self.background_pattern.name())
+            .show_ui(ui, |ui| {
+                for pattern in BackgroundPattern::ALL {
+                    ui.selectable_value(&mut self.background_pattern, pattern, pattern.name());
+                }
+            });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-camera-transition"));
+            ui.add(
+                egui::DragValue::new(&mut self.camera_transition_seconds)
+                    .clamp_range(0.0..=5.0)
+                    .speed(0.01)
+                    .suffix("s"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-preview-resolution"));
+            ui.add(
+                egui::Slider::new(&mut self.preview_resolution_scale, 0.1..=1.0)
+                    .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-preview-idle"));
+            ui.add(
+                egui::DragValue::new(&mut self.preview_idle_seconds)
+                    .clamp_range(0.0..=2.0)
+                    .speed(0.01)
+                    .suffix("s"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-max-fps"));
+            ui.add(egui::DragValue::new(&mut self.max_fps).clamp_range(0..=1000))
+                .on_hover_text(tr!("settings-max-fps-hint"));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("settings-autosave-interval"));
+            ui.add(
+                egui::DragValue::new(&mut self.autosave_interval_seconds)
+                    .clamp_range(5..=600)
+                    .suffix("s"),
+            );
+        });
+    }
+}
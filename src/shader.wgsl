@@ -12,6 +12,55 @@ struct Camera {
 @binding(0)
 var<uniform> camera: Camera;
 
+// knobs for experimenting, each gets a slider in the tweaks panel
+struct Tweaks {
+    checker_shade: f32, // 0.9, 0.0..1.0
+    grid_line_shade: f32, // 0.8, 0.0..1.0
+    grid_line_width: f32, // 1.0, 0.0..8.0
+}
+
+@group(2)
+@binding(0)
+var<uniform> tweaks: Tweaks;
+
+// specialized per pipeline permutation, the values here are only the defaults
+const DEBUG_PALETTE: u32 = 0u;
+const BACKGROUND_PATTERN: u32 = 1u;
+
+// Maps two values in 0..1 to a color, the palettes match `DebugPalette` on the rust side
+fn debug_color(value: vec2<f32>) -> vec3<f32> {
+    let v = clamp(value, vec2<f32>(0.0), vec2<f32>(1.0));
+    switch DEBUG_PALETTE {
+        case 1u: {
+            return v.x * vec3<f32>(0.902, 0.624, 0.0) + v.y * vec3<f32>(0.337, 0.706, 0.914);
+        }
+        case 2u: {
+            return vec3<f32>((v.x + v.y) * 0.5);
+        }
+        default: {
+            return vec3<f32>(v, 0.0);
+        }
+    }
+}
+
+// Brightness of the pattern drawn where rays hit nothing, the patterns match `BackgroundPattern` on the rust side
+fn background_pattern(world_position: vec2<f32>, pixel_size: f32) -> f32 {
+    switch BACKGROUND_PATTERN {
+        case 1u: {
+            let cell = vec2<i32>(floor(world_position));
+            return select(1.0, tweaks.checker_shade, ((cell.x + cell.y) & 1) == 0);
+        }
+        case 2u: {
+            // distance to the nearest grid line, in pixels
+            let distance = abs(world_position - round(world_position)) / pixel_size;
+            return select(1.0, tweaks.grid_line_shade, min(distance.x, distance.y) < tweaks.grid_line_width * 0.5);
+        }
+        default: {
+            return 1.0;
+        }
+    }
+}
+
 @compute
 @workgroup_size(16, 16)
 fn main(
@@ -29,5 +78,9 @@ fn main(
 
     let world_position = (uv - 0.5) * vec2<f32>(aspect * camera.height, camera.height) + camera.position;
 
-    textureStore(output_texture, coords, clamp(vec4<f32>(world_position - camera.player_position, 0.0, 1.0), vec4<f32>(0.0), vec4<f32>(1.0)));
+    // nothing is in the world yet, so every ray hits the background
+    let pixel_size = camera.height / f32(size.y);
+    let color = debug_color(world_position - camera.player_position) * background_pattern(world_position, pixel_size);
+
+    textureStore(output_texture, coords, vec4<f32>(color, 1.0));
 }
@@ -0,0 +1,179 @@
+use crate::localization::tr;
+use eframe::egui;
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// How often the watcher thread checks the shader file for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+struct ShaderError {
+    message: String,
+    /// 1 based, if naga could tell where the error is
+    line: Option<usize>,
+    source: String,
+}
+
+/// Watches `shader.wgsl` in the source tree and validates it with naga whenever it changes
+///
+/// Only useful when running from a checkout, otherwise the file doesn't exist and nothing happens
+pub struct ShaderReload {
+    path: PathBuf,
+    /// Signalled by the watcher thread when the file changes, `None` if there's nothing to watch
+    changes: Option<Receiver<()>>,
+    error: Option<ShaderError>,
+}
+
+impl ShaderReload {
+    pub fn new(ctx: &egui::Context) -> Self {
+        let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src/shader.wgsl"));
+        Self {
+            changes: modified(&path)
+                .and_then(|modified| watch(path.clone(), modified, ctx.clone())),
+            path,
+            error: None,
+        }
+    }
+
+    pub fn has_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns the new source if the shader changed and is valid, an invalid shader is kept for the error panel instead
+    pub fn poll(&mut self) -> Option<String> {
+        let changes = self.changes.as_ref()?;
+        // several changes can pile up between frames, only the latest contents matter
+        if changes.try_iter().count() == 0 {
+            return None;
+        }
+
+        // the file can be caught halfway through being saved, the next change will fix it
+        let source = std::fs::read_to_string(&self.path).ok()?;
+        match self.validate(&source) {
+            Ok(()) => {
+                tracing::info!(path = %self.path.display(), "reloaded shader");
+                self.error = None;
+                Some(source)
+            }
+            Err(error) => {
+                tracing::warn!("shader failed to compile:\n{}", error.message);
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    /// Shows an error naga didn't catch, such as wgpu rejecting the pipeline, in the error panel
+    pub fn set_error(&mut self, error: &anyhow::Error, source: String) {
+        let message = format!("{error:#}");
+        self.error = Some(ShaderError {
+            line: wgpu_error_line(&message),
+            message,
+            source,
+        });
+    }
+
+    fn validate(&self, source: &str) -> Result<(), ShaderError> {
+        let path = self.path.display().to_string();
+        let error = |message: String, location: Option<naga::SourceLocation>| ShaderError {
+            message,
+            line: location.map(|location| location.line_number as usize),
+            source: source.to_owned(),
+        };
+        let module = naga::front::wgsl::parse_str(source).map_err(|parse_error| {
+            error(
+                parse_error.emit_to_string_with_path(source, &path),
+                parse_error.location(source),
+            )
+        })?;
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|validation_error| {
+            error(
+                validation_error.emit_to_string_with_path(source, &path),
+                validation_error.location(source),
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Shows the last error, with the line it points at highlighted in the source
+    pub fn ui(&self, ui: &mut egui::Ui) {
+        let Some(error) = &self.error else {
+            return;
+        };
+        ui.label(tr!("shader-error-hint"));
+        ui.separator();
+        ui.label(egui::RichText::new(&error.message).monospace());
+
+        let Some(error_line) = error.line else {
+            return;
+        };
+        ui.separator();
+        // a few lines either side of the error for context
+        for (index, line) in error.source.lines().enumerate() {
+            let number = index + 1;
+            if number + 5 < error_line || number > error_line + 5 {
+                continue;
+            }
+            let text = egui::RichText::new(format!("{number:>4} | {line}")).monospace();
+            if number == error_line {
+                ui.label(
+                    text.color(ui.visuals().strong_text_color())
+                        .background_color(ui.visuals().error_fg_color.linear_multiply(0.3)),
+                );
+            } else {
+                ui.label(text);
+            }
+        }
+    }
+}
+
+/// Finds the line in wgpu's shader errors, which point at the source like `┌─ wgsl:12:5`
+fn wgpu_error_line(message: &str) -> Option<usize> {
+    let (_, location) = message.split_once("┌─")?;
+    let location = location.lines().next()?.trim();
+    let mut parts = location.rsplitn(3, ':');
+    let _column = parts.next()?;
+    parts.next()?.parse().ok()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Polls the modification time on a background thread, so the app only wakes up when the file actually changes
+fn watch(path: PathBuf, mut last_modified: SystemTime, ctx: egui::Context) -> Option<Receiver<()>> {
+    let (sender, changes) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name("Shader Watcher".into())
+        .spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            let Some(modified) = modified(&path) else {
+                continue;
+            };
+            if modified != last_modified {
+                last_modified = modified;
+                // the receiver is dropped when the app exits, which stops the thread
+                if sender.send(()).is_err() {
+                    return;
+                }
+                ctx.request_repaint();
+            }
+        });
+    match spawned {
+        Ok(_) => Some(changes),
+        Err(error) => {
+            tracing::error!("failed to start the shader watcher thread: {error}");
+            None
+        }
+    }
+}
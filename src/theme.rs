@@ -0,0 +1,113 @@
+use crate::localization::tr;
+use eframe::egui::{self, Color32, Stroke};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemePreset {
+    pub const ALL: [Self; 3] = [Self::Dark, Self::Light, Self::HighContrast];
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Dark => tr!("theme-dark"),
+            Self::Light => tr!("theme-light"),
+            Self::HighContrast => tr!("theme-high-contrast"),
+        }
+    }
+
+    fn visuals(self) -> egui::Visuals {
+        match self {
+            Self::Dark => egui::Visuals::dark(),
+            Self::Light => egui::Visuals::light(),
+            Self::HighContrast => high_contrast_visuals(),
+        }
+    }
+}
+
+/// The look of the editor UI, this doesn't affect the rendered image
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub preset: ThemePreset,
+    /// Replaces the preset's selection and hyperlink colors
+    pub accent: Option<[u8; 3]>,
+}
+
+impl Theme {
+    pub fn visuals(&self) -> egui::Visuals {
+        let mut visuals = self.preset.visuals();
+        if let Some([r, g, b]) = self.accent {
+            let accent = Color32::from_rgb(r, g, b);
+            visuals.selection.bg_fill = accent;
+            visuals.selection.stroke.color = contrasting_text_color(accent);
+            visuals.hyperlink_color = accent;
+        }
+        visuals
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label(tr!("theme"))
+            .selected_text(self.preset.name())
+            .show_ui(ui, |ui| {
+                for preset in ThemePreset::ALL {
+                    ui.selectable_value(&mut self.preset, preset, preset.name());
+                }
+            });
+
+        ui.horizontal(|ui| {
+            let mut custom_accent = self.accent.is_some();
+            if ui
+                .checkbox(&mut custom_accent, tr!("theme-custom-accent"))
+                .changed()
+            {
+                self.accent = custom_accent.then(|| {
+                    // start from the preset's accent so enabling it doesn't change anything yet
+                    let color = self.preset.visuals().selection.bg_fill;
+                    [color.r(), color.g(), color.b()]
+                });
+            }
+            if let Some(accent) = &mut self.accent {
+                ui.color_edit_button_srgb(accent);
+            }
+        });
+    }
+}
+
+fn contrasting_text_color(background: Color32) -> Color32 {
+    let luminance = 0.2126 * background.r() as f32
+        + 0.7152 * background.g() as f32
+        + 0.0722 * background.b() as f32;
+    if luminance > 140.0 {
+        Color32::BLACK
+    } else {
+        Color32::WHITE
+    }
+}
+
+fn high_contrast_visuals() -> egui::Visuals {
+    let mut visuals = egui::Visuals::dark();
+    visuals.override_text_color = Some(Color32::WHITE);
+    visuals.panel_fill = Color32::BLACK;
+    visuals.window_fill = Color32::BLACK;
+    visuals.extreme_bg_color = Color32::BLACK;
+    visuals.window_stroke = Stroke::new(2.0, Color32::WHITE);
+    for widget in [
+        &mut visuals.widgets.noninteractive,
+        &mut visuals.widgets.inactive,
+        &mut visuals.widgets.hovered,
+        &mut visuals.widgets.active,
+        &mut visuals.widgets.open,
+    ] {
+        widget.bg_stroke = Stroke::new(1.5, Color32::WHITE);
+        widget.fg_stroke.color = Color32::WHITE;
+    }
+    visuals.selection.bg_fill = Color32::from_rgb(255, 200, 0);
+    visuals.selection.stroke = Stroke::new(2.0, Color32::BLACK);
+    visuals
+}
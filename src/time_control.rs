@@ -0,0 +1,57 @@
+use crate::localization::tr;
+use eframe::egui;
+
+/// How far a single step advances animations, before the time scale is applied
+const STEP: f32 = 1.0 / 60.0;
+
+/// Pausing, single stepping and slowing down everything that animates, by gating the time step passed to it
+pub struct TimeControl {
+    paused: bool,
+    step_requested: bool,
+    time_scale: f32,
+}
+
+impl TimeControl {
+    pub fn new() -> Self {
+        Self {
+            paused: false,
+            step_requested: false,
+            time_scale: 1.0,
+        }
+    }
+
+    /// Turns the real time step into the one animations should advance by this frame
+    pub fn scale(&mut self, dt: f32) -> f32 {
+        if std::mem::take(&mut self.step_requested) {
+            STEP * self.time_scale
+        } else if self.paused {
+            0.0
+        } else {
+            dt * self.time_scale
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let (icon, hint) = if self.paused {
+            ("▶", tr!("time-resume"))
+        } else {
+            ("⏸", tr!("time-pause"))
+        };
+        if ui.button(icon).on_hover_text(hint).clicked() {
+            self.paused = !self.paused;
+        }
+        if ui
+            .add_enabled(self.paused, egui::Button::new("⏭"))
+            .on_hover_text(tr!("time-step"))
+            .clicked()
+        {
+            self.step_requested = true;
+        }
+        ui.add(
+            egui::Slider::new(&mut self.time_scale, 0.0..=4.0)
+                .suffix("x")
+                .max_decimals(2),
+        )
+        .on_hover_text(tr!("time-scale"));
+    }
+}
@@ -0,0 +1,501 @@
+use crate::{localization::tr, parameters::Parameter};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Holds the value until the next keyframe
+    Step,
+}
+
+impl Easing {
+    pub const ALL: [Self; 5] = [
+        Self::Linear,
+        Self::EaseIn,
+        Self::EaseOut,
+        Self::EaseInOut,
+        Self::Step,
+    ];
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Linear => tr!("easing-linear"),
+            Self::EaseIn => tr!("easing-ease-in"),
+            Self::EaseOut => tr!("easing-ease-out"),
+            Self::EaseInOut => tr!("easing-ease-in-out"),
+            Self::Step => tr!("easing-step"),
+        }
+    }
+
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Self::Step => 0.0,
+        }
+    }
+}
+
+pub trait Interpolate: Copy {
+    fn interpolate(self, other: Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct CameraPose {
+    pub position: cgmath::Vector2<f32>,
+    pub height: f32,
+}
+
+impl Interpolate for CameraPose {
+    fn interpolate(self, other: Self, t: f32) -> Self {
+        Self {
+            position: self.position + (other.position - self.position) * t,
+            // interpolate the height exponentially so zooming looks like a constant speed
+            height: self.height * (other.height / self.height).powf(t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Keyframe<T> {
+    pub time: f32,
+    pub value: T,
+    /// The easing used between this keyframe and the next one
+    pub easing: Easing,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Track<T> {
+    /// Always sorted by time
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T: Interpolate> Track<T> {
+    pub fn new() -> Self {
+        Self { keyframes: vec![] }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keyframes.len()
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    pub fn sample(&self, time: f32) -> Option<T> {
+        let next = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time);
+        match (
+            next.checked_sub(1).map(|i| &self.keyframes[i]),
+            self.keyframes.get(next),
+        ) {
+            (None, None) => None,
+            (Some(keyframe), None) | (None, Some(keyframe)) => Some(keyframe.value),
+            (Some(previous), Some(next)) => {
+                let t = (time - previous.time) / (next.time - previous.time);
+                Some(
+                    previous
+                        .value
+                        .interpolate(next.value, previous.easing.apply(t)),
+                )
+            }
+        }
+    }
+
+    pub fn add_keyframe(&mut self, keyframe: Keyframe<T>) {
+        let index = self
+            .keyframes
+            .partition_point(|other| other.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    /// Shows the keyframe list, returning a time to jump to if one was requested
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        id_source: impl std::hash::Hash,
+        current: T,
+        mut value_ui: impl FnMut(&mut egui::Ui, &mut T),
+    ) -> Option<f32> {
+        let mut go_to = None;
+        let mut resort = false;
+        let mut remove = None;
+        ui.push_id(id_source, |ui| {
+            egui::Grid::new("Keyframes").show(ui, |ui| {
+                for (i, keyframe) in self.keyframes.iter_mut().enumerate() {
+                    resort |= ui
+                        .add(
+                            egui::DragValue::new(&mut keyframe.time)
+                                .clamp_range(0.0..=f32::INFINITY)
+                                .speed(0.01)
+                                .suffix("s"),
+                        )
+                        .changed();
+                    value_ui(ui, &mut keyframe.value);
+                    egui::ComboBox::from_id_source(("Keyframe Easing", i))
+                        .selected_text(keyframe.easing.name())
+                        .show_ui(ui, |ui| {
+                            for easing in Easing::ALL {
+                                ui.selectable_value(&mut keyframe.easing, easing, easing.name());
+                            }
+                        });
+                    if ui.button(tr!("timeline-set-to-current")).clicked() {
+                        keyframe.value = current;
+                    }
+                    if ui.button(tr!("timeline-go-to")).clicked() {
+                        go_to = Some(keyframe.time);
+                    }
+                    if ui.button(tr!("timeline-delete")).clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(i) = remove {
+            self.keyframes.remove(i);
+        }
+        if resort {
+            self.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        }
+        go_to
+    }
+}
+
+/// The values every track has at the current time, which should be applied before the frame is rendered
+pub struct TimelineSample {
+    pub camera: Option<CameraPose>,
+    pub parameters: Vec<(Parameter, f32)>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Timeline {
+    camera: Track<CameraPose>,
+    parameters: Vec<(Parameter, Track<f32>)>,
+    time: f32,
+    #[serde(skip)]
+    playing: bool,
+    looping: bool,
+    /// Set when the tracks should be applied at the current time even though the timeline isn't playing
+    #[serde(skip)]
+    scrubbed: bool,
+    #[serde(skip)]
+    rendering: bool,
+    #[serde(skip)]
+    render_started: bool,
+    #[serde(skip)]
+    render_finished: bool,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self {
+            camera: Track::new(),
+            parameters: vec![],
+            time: 0.0,
+            playing: false,
+            looping: false,
+            scrubbed: false,
+            rendering: false,
+            render_started: false,
+            render_finished: false,
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.parameters
+            .iter()
+            .map(|(_, track)| track.duration())
+            .fold(self.camera.duration(), f32::max)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.camera.is_empty() && self.parameters.iter().all(|(_, track)| track.is_empty())
+    }
+
+    pub fn keyframe_count(&self) -> usize {
+        self.parameters
+            .iter()
+            .map(|(_, track)| track.len())
+            .sum::<usize>()
+            + self.camera.len()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn sample(&self, time: f32) -> TimelineSample {
+        TimelineSample {
+            camera: self.camera.sample(time),
+            parameters: self
+                .parameters
+                .iter()
+                .filter_map(|(parameter, track)| Some((*parameter, track.sample(time)?)))
+                .collect(),
+        }
+    }
+
+    /// Returns the values for this frame if the timeline is controlling them, then advances time by `dt`
+    pub fn update(&mut self, dt: f32) -> Option<TimelineSample> {
+        if !self.playing && !std::mem::take(&mut self.scrubbed) {
+            return None;
+        }
+        let sample = self.sample(self.time);
+
+        if self.playing {
+            self.time += dt;
+            let duration = self.duration();
+            if self.time > duration {
+                if self.rendering {
+                    self.playing = false;
+                    self.rendering = false;
+                    self.render_finished = true;
+                } else if self.looping && duration > 0.0 {
+                    self.time %= duration;
+                } else {
+                    self.time = duration;
+                    self.playing = false;
+                }
+            }
+        }
+
+        Some(sample)
+    }
+
+    /// Starts playing from the beginning if the end has been reached, does nothing while rendering since the
+    /// capture depends on the timeline playing through once
+    pub fn toggle_playing(&mut self) {
+        if self.rendering {
+            return;
+        }
+        self.playing = !self.playing;
+        if self.playing && self.time >= self.duration() {
+            self.time = 0.0;
+        }
+    }
+
+    /// Returns true once when the user asks for the animation to be rendered, the capture should be started
+    pub fn take_render_started(&mut self) -> bool {
+        std::mem::take(&mut self.render_started)
+    }
+
+    /// Returns true once after the last frame of a render, the capture should be stopped
+    pub fn take_render_finished(&mut self) -> bool {
+        std::mem::take(&mut self.render_finished)
+    }
+
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        current_camera: CameraPose,
+        current: impl Fn(Parameter) -> f32,
+    ) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.rendering,
+                    egui::Button::new(if self.playing {
+                        tr!("timeline-pause")
+                    } else {
+                        tr!("timeline-play")
+                    }),
+                )
+                .clicked()
+            {
+                self.toggle_playing();
+            }
+            ui.checkbox(&mut self.looping, tr!("timeline-loop"));
+            ui.add_enabled_ui(!self.rendering && !self.is_empty(), |ui| {
+                if ui
+                    .button(tr!("timeline-render"))
+                    .on_hover_text(tr!("timeline-render-tooltip"))
+                    .clicked()
+                {
+                    self.time = 0.0;
+                    self.playing = true;
+                    self.rendering = true;
+                    self.render_started = true;
+                }
+            });
+        });
+
+        let duration = self.duration();
+        ui.horizontal(|ui| {
+            ui.label(tr!("timeline-time"));
+            if ui
+                .add(egui::Slider::new(&mut self.time, 0.0..=duration).suffix("s"))
+                .changed()
+            {
+                self.scrubbed = true;
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.strong(tr!("timeline-camera-track"));
+            if ui.button(tr!("timeline-add-keyframe")).clicked() {
+                self.camera.add_keyframe(Keyframe {
+                    time: self.time,
+                    value: current_camera,
+                    easing: Easing::EaseInOut,
+                });
+            }
+        });
+        let mut go_to = self.camera.ui(ui, "Camera", current_camera, |_, _| {});
+
+        let mut remove = None;
+        for (i, (parameter, track)) in self.parameters.iter_mut().enumerate() {
+            let parameter = *parameter;
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.strong(parameter.name());
+                if ui.button(tr!("timeline-add-keyframe")).clicked() {
+                    track.add_keyframe(Keyframe {
+                        time: self.time,
+                        value: current(parameter),
+                        easing: Easing::EaseInOut,
+                    });
+                }
+                if ui.button(tr!("timeline-remove-track")).clicked() {
+                    remove = Some(i);
+                }
+            });
+            go_to = go_to.or(track.ui(ui, parameter, current(parameter), |ui, value| {
+                ui.add(egui::DragValue::new(value).speed(0.01));
+            }));
+        }
+        if let Some(i) = remove {
+            self.parameters.remove(i);
+        }
+
+        ui.separator();
+        ui.menu_button(tr!("timeline-add-track"), |ui| {
+            for parameter in Parameter::ALL {
+                let tracked = self.parameters.iter().any(|(other, _)| *other == parameter);
+                if ui
+                    .add_enabled(!tracked, egui::Button::new(parameter.name()))
+                    .clicked()
+                {
+                    self.parameters.push((parameter, Track::new()));
+                    ui.close_menu();
+                }
+            }
+        });
+
+        if let Some(time) = go_to {
+            self.time = time;
+            self.scrubbed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(keyframes: &[(f32, f32, Easing)]) -> Track<f32> {
+        let mut track = Track::new();
+        for &(time, value, easing) in keyframes {
+            track.add_keyframe(Keyframe {
+                time,
+                value,
+                easing,
+            });
+        }
+        track
+    }
+
+    #[test]
+    fn empty_track_has_no_value() {
+        assert_eq!(Track::<f32>::new().sample(1.0), None);
+    }
+
+    #[test]
+    fn holds_the_first_and_last_keyframes() {
+        let track = track(&[(1.0, 10.0, Easing::Linear), (2.0, 20.0, Easing::Linear)]);
+        assert_eq!(track.sample(0.0), Some(10.0));
+        assert_eq!(track.sample(1.0), Some(10.0));
+        assert_eq!(track.sample(2.0), Some(20.0));
+        assert_eq!(track.sample(5.0), Some(20.0));
+    }
+
+    #[test]
+    fn interpolates_between_keyframes() {
+        let track = track(&[(0.0, 0.0, Easing::Linear), (2.0, 10.0, Easing::Step)]);
+        assert_eq!(track.sample(0.5), Some(2.5));
+        assert_eq!(track.sample(1.0), Some(5.0));
+    }
+
+    #[test]
+    fn keyframes_at_the_same_time_jump_to_the_later_one() {
+        let track = track(&[
+            (0.0, 0.0, Easing::Linear),
+            (1.0, 1.0, Easing::Linear),
+            (1.0, 2.0, Easing::Linear),
+            (2.0, 3.0, Easing::Linear),
+        ]);
+        assert_eq!(track.sample(0.5), Some(0.5));
+        assert_eq!(track.sample(1.0), Some(2.0));
+        assert_eq!(track.sample(1.5), Some(2.5));
+    }
+
+    #[test]
+    fn easings_start_at_0_and_end_at_1() {
+        for easing in Easing::ALL {
+            assert_eq!(easing.apply(0.0), 0.0);
+            if easing != Easing::Step {
+                assert_eq!(easing.apply(1.0), 1.0);
+            }
+        }
+        assert_eq!(Easing::Step.apply(0.99), 0.0);
+        assert_eq!(Easing::EaseIn.apply(0.5), 0.25);
+        assert_eq!(Easing::EaseOut.apply(0.5), 0.75);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn camera_height_is_interpolated_exponentially() {
+        let from = CameraPose {
+            position: cgmath::vec2(0.0, 0.0),
+            height: 1.0,
+        };
+        let to = CameraPose {
+            position: cgmath::vec2(4.0, -2.0),
+            height: 100.0,
+        };
+        let halfway = from.interpolate(to, 0.5);
+        assert_eq!(halfway.position, cgmath::vec2(2.0, -1.0));
+        assert!((halfway.height - 10.0).abs() < 1e-4);
+        assert_eq!(from.interpolate(to, 1.0).height, 100.0);
+    }
+
+    #[test]
+    fn playback_cant_be_toggled_while_rendering() {
+        let mut timeline = Timeline::new();
+        timeline.playing = true;
+        timeline.rendering = true;
+        timeline.time = 1.0;
+        timeline.toggle_playing();
+        assert!(timeline.playing);
+        assert_eq!(timeline.time, 1.0);
+    }
+}
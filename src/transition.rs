@@ -0,0 +1,35 @@
+use crate::timeline::{CameraPose, Easing, Interpolate};
+
+/// Animates the camera to a new pose instead of cutting to it
+pub struct CameraTransition {
+    from: CameraPose,
+    to: CameraPose,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl CameraTransition {
+    pub fn new(from: CameraPose, to: CameraPose, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: 0.0,
+            duration,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Advances the transition by `dt`, returning the pose the camera should have now
+    pub fn update(&mut self, dt: f32) -> CameraPose {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        let t = if self.duration > 0.0 {
+            self.elapsed / self.duration
+        } else {
+            1.0
+        };
+        self.from.interpolate(self.to, Easing::EaseInOut.apply(t))
+    }
+}
@@ -0,0 +1,143 @@
+use crate::localization::tr;
+use eframe::egui;
+use std::collections::HashMap;
+
+/// Parts of the UI that a tutorial step can point at
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    Viewport,
+    CameraButton,
+    TimelineButton,
+    CaptureButton,
+    HelpMenu,
+}
+
+struct Step {
+    /// Message ids of the title and text
+    title: &'static str,
+    text: &'static str,
+    target: Option<Target>,
+}
+
+const STEPS: &[Step] = &[
+    Step {
+        title: "tutorial-welcome-title",
+        text: "tutorial-welcome-text",
+        target: None,
+    },
+    Step {
+        title: "tutorial-viewport-title",
+        text: "tutorial-viewport-text",
+        target: Some(Target::Viewport),
+    },
+    Step {
+        title: "tutorial-camera-title",
+        text: "tutorial-camera-text",
+        target: Some(Target::CameraButton),
+    },
+    Step {
+        title: "tutorial-timeline-title",
+        text: "tutorial-timeline-text",
+        target: Some(Target::TimelineButton),
+    },
+    Step {
+        title: "tutorial-capture-title",
+        text: "tutorial-capture-text",
+        target: Some(Target::CaptureButton),
+    },
+    Step {
+        title: "tutorial-help-title",
+        text: "tutorial-help-text",
+        target: Some(Target::HelpMenu),
+    },
+];
+
+/// A guided tour that highlights parts of the UI one step at a time
+pub struct Tutorial {
+    step: Option<usize>,
+    completed: bool,
+    targets: HashMap<Target, egui::Rect>,
+}
+
+impl Tutorial {
+    /// Starts the tour right away unless it has been completed before
+    pub fn new(completed: bool) -> Self {
+        Self {
+            step: (!completed).then_some(0),
+            completed,
+            targets: HashMap::new(),
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn start(&mut self) {
+        self.step = Some(0);
+    }
+
+    /// Records where a target was drawn this frame, so the current step can point at it
+    pub fn register(&mut self, target: Target, rect: egui::Rect) {
+        self.targets.insert(target, rect);
+    }
+
+    fn finish(&mut self) {
+        self.step = None;
+        self.completed = true;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let Some(index) = self.step else {
+            return;
+        };
+        let step = &STEPS[index];
+        let target = step
+            .target
+            .and_then(|target| self.targets.get(&target).copied());
+
+        if let Some(rect) = target {
+            ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("Tutorial Highlight"),
+            ))
+            .rect_stroke(
+                rect.expand(4.0),
+                4.0,
+                egui::Stroke::new(3.0, ctx.style().visuals.selection.bg_fill),
+            );
+        }
+
+        let mut window = egui::Window::new(tr!(step.title))
+            .id(egui::Id::new("Tutorial"))
+            .collapsible(false)
+            .resizable(false);
+        window = match target {
+            // place the window next to the target, clamped to the screen
+            Some(rect) => {
+                let position = rect.left_bottom() + egui::vec2(0.0, 12.0);
+                window.current_pos(ctx.screen_rect().clamp(position))
+            }
+            None => window.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO),
+        };
+        window.show(ctx, |ui| {
+            ui.label(tr!(step.text));
+            ui.horizontal(|ui| {
+                ui.label(format!("{}/{}", index + 1, STEPS.len()));
+                if index > 0 && ui.button(tr!("tutorial-back")).clicked() {
+                    self.step = Some(index - 1);
+                }
+                if index + 1 < STEPS.len() {
+                    if ui.button(tr!("tutorial-next")).clicked() {
+                        self.step = Some(index + 1);
+                    }
+                    if ui.button(tr!("tutorial-skip")).clicked() {
+                        self.finish();
+                    }
+                } else if ui.button(tr!("tutorial-done")).clicked() {
+                    self.finish();
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,219 @@
+use crate::localization::tr;
+use anyhow::{bail, Context};
+use eframe::egui;
+use std::ops::RangeInclusive;
+
+/// The most bytes of tweaks the uniform buffer can hold, so it doesn't have to be recreated when the shader reloads
+pub const BUFFER_SIZE: u64 = 256;
+
+/// The name of the uniform struct the shader declares its tweaks in
+const STRUCT_NAME: &str = "Tweaks";
+
+#[derive(Clone, PartialEq)]
+struct Tweak {
+    name: String,
+    /// Byte offset of the member in the uniform struct
+    offset: u32,
+    default: f32,
+    range: RangeInclusive<f32>,
+    value: f32,
+}
+
+/// Knobs the shader exposes as `f32` members of a `Tweaks` uniform struct, the panel is generated from the shader
+/// so experimenting with a new constant doesn't need any rust code
+///
+/// A trailing comment on a member sets its default and slider range, for example `shade: f32, // 0.9, 0.0..1.0`
+#[derive(Clone, PartialEq, Default)]
+pub struct Tweaks {
+    tweaks: Vec<Tweak>,
+    size: u32,
+}
+
+impl Tweaks {
+    pub fn reflect(source: &str) -> anyhow::Result<Self> {
+        let module = naga::front::wgsl::parse_str(source)
+            .map_err(|error| anyhow::anyhow!(error.emit_to_string(source)))?;
+
+        let Some(ty) = module.global_variables.iter().find_map(|(_, variable)| {
+            (variable.space == naga::AddressSpace::Uniform
+                && module.types[variable.ty].name.as_deref() == Some(STRUCT_NAME))
+            .then_some(variable.ty)
+        }) else {
+            return Ok(Self::default());
+        };
+        // only the struct's own declaration is searched for comments, so a member of another struct
+        // with the same name can't be mistaken for it
+        let declaration = module
+            .types
+            .get_span(ty)
+            .to_range()
+            .and_then(|range| source.get(range))
+            .unwrap_or(source);
+        let ty = &module.types[ty];
+        let naga::TypeInner::Struct { members, span } = &ty.inner else {
+            bail!("{STRUCT_NAME} must be a struct");
+        };
+        if *span as u64 > BUFFER_SIZE {
+            bail!("{STRUCT_NAME} is {span} bytes, at most {BUFFER_SIZE} fit in the buffer");
+        }
+
+        let tweaks = members
+            .iter()
+            .map(|member| {
+                let name = member.name.clone().unwrap_or_default();
+                if !matches!(
+                    module.types[member.ty].inner,
+                    naga::TypeInner::Scalar {
+                        kind: naga::ScalarKind::Float,
+                        width: 4,
+                    }
+                ) {
+                    bail!("tweak `{name}` must be an f32");
+                }
+                let (default, range) = annotation(declaration, &name)
+                    .with_context(|| format!("comment on tweak `{name}`"))?;
+                Ok(Tweak {
+                    name,
+                    offset: member.offset,
+                    default,
+                    range,
+                    value: default,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            tweaks,
+            size: *span,
+        })
+    }
+
+    /// Takes the tweaks from a reloaded shader, keeping the values of the ones that are still there
+    pub fn reload(&mut self, mut tweaks: Self) {
+        for tweak in &mut tweaks.tweaks {
+            if let Some(old) = self.tweaks.iter().find(|old| old.name == tweak.name) {
+                tweak.value = old.value.clamp(*tweak.range.start(), *tweak.range.end());
+            }
+        }
+        *self = tweaks;
+    }
+
+    pub fn get(&self, name: &str) -> Option<f32> {
+        self.tweaks
+            .iter()
+            .find(|tweak| tweak.name == name)
+            .map(|tweak| tweak.value)
+    }
+
+    /// The contents of the uniform struct, empty if the shader doesn't declare one
+    pub fn bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; self.size as usize];
+        for tweak in &self.tweaks {
+            let offset = tweak.offset as usize;
+            bytes[offset..offset + 4].copy_from_slice(&tweak.value.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if self.tweaks.is_empty() {
+            ui.weak(tr!("tweaks-empty"));
+            return;
+        }
+        egui::Grid::new("Tweaks").show(ui, |ui| {
+            for tweak in &mut self.tweaks {
+                ui.label(&tweak.name);
+                ui.add(egui::Slider::new(&mut tweak.value, tweak.range.clone()));
+                if ui
+                    .add_enabled(tweak.value != tweak.default, egui::Button::new("⟲"))
+                    .on_hover_text(tr!("tweaks-reset"))
+                    .clicked()
+                {
+                    tweak.value = tweak.default;
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
+
+/// Parses `// default, min..max` after the member's declaration in `source`, members without a comment default to 0 in 0..1
+fn annotation(source: &str, name: &str) -> anyhow::Result<(f32, RangeInclusive<f32>)> {
+    let comment = source
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with(&format!("{name}:")))
+        .and_then(|line| line.split_once("//"))
+        .map(|(_, comment)| comment.trim());
+    let Some(comment) = comment else {
+        return Ok((0.0, 0.0..=1.0));
+    };
+    let (default, range) = comment
+        .split_once(',')
+        .context("expected `default, min..max`")?;
+    let (min, max) = range
+        .trim()
+        .split_once("..")
+        .context("expected a range like `0.0..1.0`")?;
+    let (default, min, max) = (
+        default.trim().parse::<f32>()?,
+        min.trim().parse::<f32>()?,
+        max.trim().parse::<f32>()?,
+    );
+    if min > max {
+        bail!("the range is empty");
+    }
+    Ok((default.clamp(min, max), min..=max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{annotation, Tweaks};
+
+    #[test]
+    fn reads_comments_from_the_tweaks_struct_only() {
+        let source = "
+struct Other {
+    shade: f32, // 5, 0..10
+}
+
+struct Tweaks {
+    shade: f32, // 0.5, 0..1
+}
+
+@group(0) @binding(0)
+var<uniform> other: Other;
+@group(0) @binding(1)
+var<uniform> tweaks: Tweaks;
+";
+        let tweaks = Tweaks::reflect(source).unwrap();
+        assert_eq!(tweaks.get("shade"), Some(0.5));
+        assert_eq!(tweaks.tweaks[0].range, 0.0..=1.0);
+    }
+
+    #[test]
+    fn members_without_a_comment_default_to_0_in_0_to_1() {
+        let source = "struct Tweaks {\n    shade: f32,\n}";
+        assert_eq!(annotation(source, "shade").unwrap(), (0.0, 0.0..=1.0));
+    }
+
+    #[test]
+    fn parses_the_default_and_range() {
+        let source = "struct Tweaks {\n    shade: f32, // 0.9, 0.0..1.0\n    glow: f32, // -1, -2.5 .. 2.5\n}";
+        assert_eq!(annotation(source, "shade").unwrap(), (0.9, 0.0..=1.0));
+        assert_eq!(annotation(source, "glow").unwrap(), (-1.0, -2.5..=2.5));
+    }
+
+    #[test]
+    fn clamps_the_default_into_the_range() {
+        let source = "struct Tweaks {\n    shade: f32, // 5, 0..1\n}";
+        assert_eq!(annotation(source, "shade").unwrap(), (1.0, 0.0..=1.0));
+    }
+
+    #[test]
+    fn rejects_malformed_comments() {
+        for comment in ["0.5", "0.5, 1", "half, 0..1", "0.5, 1..0"] {
+            let source = format!("struct Tweaks {{\n    shade: f32, // {comment}\n}}");
+            assert!(annotation(&source, "shade").is_err(), "{comment}");
+        }
+    }
+}